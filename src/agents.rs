@@ -1,19 +1,25 @@
-use std::env;
-#[cfg(feature = "image")]
-use std::sync::Arc;
-use std::sync::OnceLock;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
 
+use base64::Engine;
 use im::{Vector, hashmap};
 use modular_agent_core::photon_rs::PhotonImage;
 use modular_agent_core::{
-    Agent, AgentContext, AgentData, AgentError, AgentOutput, AgentSpec, AgentValue, AsAgent,
-    Message, ModularAgent, async_trait, modular_agent,
+    Agent, AgentConfigs, AgentContext, AgentData, AgentError, AgentOutput, AgentSpec, AgentValue,
+    AsAgent, Message, ModularAgent, async_trait, modular_agent,
 };
+use regex::Regex;
+use slack_morphism::errors::SlackClientError;
 use slack_morphism::prelude::*;
-use tokio::sync::mpsc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio::sync::{Notify, mpsc};
 use tracing::error;
 
+use crate::blocks;
 use crate::mrkdwn;
+use crate::slack::{self, get_client};
 
 static CATEGORY: &str = "Slack";
 
@@ -23,79 +29,198 @@ static PORT_MESSAGE: &str = "message";
 static PORT_VALUE: &str = "value";
 static PORT_VALUES: &str = "values";
 static PORT_CHANNELS: &str = "channels";
+static PORT_CHANNEL: &str = "channel";
+static PORT_BLOCKS: &str = "blocks";
+static PORT_ERROR: &str = "error";
+static PORT_REACTIONS: &str = "reactions";
+static PORT_MESSAGES: &str = "messages";
+static PORT_MEMBERS: &str = "members";
+static PORT_FILE: &str = "file";
+static PORT_EVENT: &str = "event";
+static PORT_CHANGE: &str = "change";
+static PORT_INTERACTION: &str = "interaction";
+static PORT_RAW: &str = "raw";
+static PORT_STATUS: &str = "status";
 
 static CONFIG_CHANNEL: &str = "channel";
+static CONFIG_USER: &str = "user";
 static CONFIG_LIMIT: &str = "limit";
 static CONFIG_CONVERT_MARKDOWN: &str = "convert_markdown";
+static CONFIG_DATE_FORMAT: &str = "date_format";
+static CONFIG_CONVERT_SPECIAL_MENTIONS: &str = "convert_special_mentions";
+static CONFIG_ESCAPE_SPECIAL_MENTIONS: &str = "escape_special_mentions";
+static CONFIG_INCLUDE_PERMALINK: &str = "include_permalink";
+static CONFIG_REPLY_BROADCAST: &str = "reply_broadcast";
+static CONFIG_UNFURL_LINKS: &str = "unfurl_links";
+static CONFIG_UNFURL_MEDIA: &str = "unfurl_media";
+static CONFIG_USERNAME: &str = "username";
+static CONFIG_ICON_EMOJI: &str = "icon_emoji";
+static CONFIG_ICON_URL: &str = "icon_url";
+static CONFIG_MAX_MESSAGE_LENGTH: &str = "max_message_length";
+static CONFIG_RENDER_BLOCKS: &str = "render_blocks";
+static CONFIG_MAX_RETRIES: &str = "max_retries";
+static CONFIG_WEBHOOK_URL: &str = "webhook_url";
+static CONFIG_USERGROUP: &str = "usergroup";
+static CONFIG_AUTO_REPLY_IN_THREAD: &str = "auto_reply_in_thread";
+static CONFIG_NAME: &str = "name";
+static CONFIG_FILENAME: &str = "filename";
+static CONFIG_IMAGE_FORMAT: &str = "image_format";
+static CONFIG_IMAGE_QUALITY: &str = "image_quality";
+static CONFIG_SNIPPET_THRESHOLD: &str = "snippet_threshold";
+static CONFIG_CANVAS_ID: &str = "canvas_id";
+static CONFIG_TITLE: &str = "title";
+static CONFIG_OPERATION: &str = "operation";
+static CONFIG_FETCH_ALL: &str = "fetch_all";
+static CONFIG_MAX_MESSAGES: &str = "max_messages";
+static CONFIG_OLDEST: &str = "oldest";
+static CONFIG_LATEST: &str = "latest";
+static CONFIG_INCLUDE_REACTIONS: &str = "include_reactions";
+static CONFIG_RESOLVE_USERS: &str = "resolve_users";
+static CONFIG_EXCLUDE_SUBTYPES: &str = "exclude_subtypes";
+static CONFIG_EMIT_INDIVIDUALLY: &str = "emit_individually";
+static CONFIG_INCLUDE_PERMALINKS: &str = "include_permalinks";
+static CONFIG_BOT_USER: &str = "bot_user";
+static CONFIG_INCLUDE_NAMES: &str = "include_names";
+static CONFIG_INCLUDE_PROFILES: &str = "include_profiles";
+static CONFIG_TYPES: &str = "types";
+static CONFIG_FILETYPE: &str = "filetype";
+static CONFIG_EXCLUDE_BOTS: &str = "exclude_bots";
+static CONFIG_EXCLUDE_DELETED: &str = "exclude_deleted";
+static CONFIG_TIME: &str = "time";
+static CONFIG_INCREMENTAL: &str = "incremental";
+static CONFIG_CURSOR: &str = "cursor";
+static CONFIG_MAX_CHANNELS: &str = "max_channels";
+static CONFIG_EXCLUDE_ARCHIVED: &str = "exclude_archived";
+static CONFIG_NAME_FILTER: &str = "name_filter";
+static CONFIG_IS_PRIVATE: &str = "is_private";
+static CONFIG_USERS: &str = "users";
+static CONFIG_FIELD: &str = "field";
+static CONFIG_ENRICH: &str = "enrich";
+static CONFIG_TRIGGER: &str = "trigger";
+static CONFIG_ALLOWED_USERS: &str = "allowed_users";
+static CONFIG_BLOCKED_USERS: &str = "blocked_users";
+static CONFIG_TEXT_FILTER: &str = "text_filter";
+static CONFIG_THREAD_MODE: &str = "thread_mode";
+static CONFIG_RESOLVE_MENTIONS: &str = "resolve_mentions";
+static CONFIG_ACTION: &str = "action";
+static CONFIG_FORMAT: &str = "format";
+static CONFIG_PATH: &str = "path";
+static CONFIG_INCLUDE_THREADS: &str = "include_threads";
 static CONFIG_SLACK_BOT_TOKEN: &str = "slack_bot_token";
 static CONFIG_SLACK_APP_TOKEN: &str = "slack_app_token";
-
-type HyperConnector = SlackClientHyperConnector<SlackHyperHttpsConnector>;
-
-static CLIENT: OnceLock<SlackClient<HyperConnector>> = OnceLock::new();
-
-fn get_client() -> &'static SlackClient<HyperConnector> {
-    CLIENT.get_or_init(|| {
-        rustls::crypto::ring::default_provider()
-            .install_default()
-            .expect("Failed to initialize rustls crypto provider");
-        SlackClient::new(
-            SlackClientHyperConnector::new().expect("Failed to create Slack client HTTP connector"),
-        )
-    })
-}
+static CONFIG_SLACK_USER_TOKEN: &str = "slack_user_token";
 
 fn get_token(ma: &ModularAgent) -> Result<SlackApiToken, AgentError> {
-    let token_str = if let Some(global_token) = ma
-        .get_global_configs(SlackPostAgent::DEF_NAME)
-        .and_then(|cfg| cfg.get_string(CONFIG_SLACK_BOT_TOKEN).ok())
-        .filter(|key| !key.is_empty())
-    {
-        global_token
-    } else {
-        env::var("SLACK_BOT_TOKEN")
-            .map_err(|_| AgentError::InvalidValue("SLACK_BOT_TOKEN not set".to_string()))?
-    };
-
-    Ok(SlackApiToken::new(SlackApiTokenValue(token_str)))
+    slack::bot_token(
+        ma.get_global_configs(SlackPostAgent::DEF_NAME)
+            .and_then(|cfg| cfg.get_string(CONFIG_SLACK_BOT_TOKEN).ok()),
+    )
 }
 
 fn get_app_token(ma: &ModularAgent) -> Result<SlackApiToken, AgentError> {
-    let token_str = if let Some(global_token) = ma
-        .get_global_configs(SlackListenerAgent::DEF_NAME)
-        .and_then(|cfg| cfg.get_string(CONFIG_SLACK_APP_TOKEN).ok())
-        .filter(|key| !key.is_empty())
-    {
-        global_token
-    } else {
-        env::var("SLACK_APP_TOKEN")
-            .map_err(|_| AgentError::InvalidValue("SLACK_APP_TOKEN not set".to_string()))?
-    };
+    slack::app_token(
+        ma.get_global_configs(SlackListenerAgent::DEF_NAME)
+            .and_then(|cfg| cfg.get_string(CONFIG_SLACK_APP_TOKEN).ok()),
+    )
+}
 
-    Ok(SlackApiToken::new(SlackApiTokenValue(token_str)))
+fn get_user_token(ma: &ModularAgent) -> Result<SlackApiToken, AgentError> {
+    slack::user_token(
+        ma.get_global_configs(SlackStarAgent::DEF_NAME)
+            .and_then(|cfg| cfg.get_string(CONFIG_SLACK_USER_TOKEN).ok()),
+    )
 }
 
 /// Agent for posting messages to Slack channels.
 ///
 /// # Configuration
 /// - `channel`: The Slack channel name (e.g., "#general") or channel ID
+/// - `convert_markdown`: Convert Markdown/HTML to Slack mrkdwn (default: true)
+/// - `date_format`: Slack date format for rendering ISO timestamps in each reader's
+///   timezone (e.g., `{date_short_pretty} at {time}`). Empty leaves timestamps as-is.
+/// - `convert_special_mentions`: Convert `@here`/`@channel`/`@everyone` to mentions
+///   that notify the channel (default: false, left as plain text)
+/// - `escape_special_mentions`: Escape `@here`/`@channel`/`@everyone` (and `<!here>`
+///   tokens) in the text and blocks so nobody is notified, even without Markdown
+///   conversion. Wins over `convert_special_mentions` (default: false)
+/// - `include_permalink`: Add a `permalink` field to the result via `chat.getPermalink`
+///   (default: false)
+/// - `reply_broadcast`: Also show thread replies in the channel (default: false)
+/// - `unfurl_links`: Unfurl text-based links (default: false)
+/// - `unfurl_media`: Unfurl media links (default: true)
+/// - `username`, `icon_emoji`, `icon_url`: Post with a custom persona
+///   (requires the `chat:write.customize` scope)
+/// - `max_message_length`: Text longer than this is split into several messages posted
+///   in the same thread (default: 4000, 0 disables). Not applied when `blocks` are given.
+/// - `render_blocks`: Render Markdown text as Block Kit blocks (headers, sections,
+///   preformatted code, dividers) instead of a single mrkdwn text (default: false).
+///   More than 50 blocks are posted as several messages in the same thread.
+/// - `max_retries`: Retries when Slack rate-limits a post, waiting for `Retry-After`
+///   with exponential backoff (default: 3, 0 disables)
+/// - `usergroup`: User group handle (e.g. `oncall`) to mention at the top of each
+///   message, resolved to `<!subteam^ID>` via `usergroups.list`. `@handle` in the
+///   text is converted as well.
+/// - `auto_reply_in_thread`: When the input carries a `ts` (e.g. a Listener message),
+///   reply in that message's thread (default: false)
+/// - `image_format`: Encoding for uploaded images, `png` (default), `jpeg` or `webp`
+/// - `image_quality`: JPEG quality from 1 to 100 (default: 80)
+/// - `snippet_threshold`: Code blocks longer than this many characters are uploaded as
+///   snippet files in the message's thread instead of posted inline (default: 0, disabled)
 ///
 /// # Input
-/// - `message`: String message or object with `text`, `blocks`, `thread_ts` fields
+/// - `message`: String message or object with `text`, `blocks`, `thread_ts`, `channel`,
+///   `reply_broadcast`, `username`, `icon_emoji`, `icon_url` fields. All but `text`,
+///   `blocks` and `thread_ts` override the configuration for that message. A `metadata`
+///   object (`event_type`, `event_payload`) is attached to the message, and `ts` is
+///   used by `auto_reply_in_thread`. `also_send_to_channel` decides for that message
+///   whether a thread reply also appears in the channel, regardless of `reply_broadcast`.
+///   An image, a Message with an image, an object with `image` (plus optional `filename`,
+///   `title`, `alt_text`), or an array of them is uploaded as image files grouped in a
+///   single message, with the text as the comment.
+/// - `channel`: Channel name or ID (or an object with `id`) used for subsequent messages
+///   instead of the configured channel
 ///
 /// # Output
-/// - `result`: Object containing `ok`, `ts`, `channel` on success
+/// - `result`: Object containing `ok`, `ts`, `channel`, `ts_list` (and `permalink`) on success.
+///   Image uploads report `file_id` (the first file) and `file_ids` instead of `ts`.
+///   `ts` is the first message; `ts_list` holds every message when the text was split.
+///   Snippets add `file_ids`, or `snippet_error` when their upload failed after the
+///   message was posted.
+/// - `error`: Object containing `ok` (false), `error` (Slack error code such as
+///   `channel_not_found` or `ratelimited`), `message` and `channel` when the channel
+///   cannot be resolved or Slack rejects the post, or `ok`, `error` and `message`
+///   when the `usergroup` lookup fails
 #[modular_agent(
     title = "Post",
     category = CATEGORY,
-    inputs = [PORT_MESSAGE],
-    outputs = [PORT_RESULT],
+    inputs = [PORT_MESSAGE, PORT_CHANNEL],
+    outputs = [PORT_RESULT, PORT_ERROR],
     string_config(name = CONFIG_CHANNEL),
     boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_INCLUDE_PERMALINK),
+    boolean_config(name = CONFIG_REPLY_BROADCAST),
+    boolean_config(name = CONFIG_UNFURL_LINKS),
+    boolean_config(name = CONFIG_UNFURL_MEDIA, default = true),
+    string_config(name = CONFIG_USERNAME),
+    string_config(name = CONFIG_ICON_EMOJI),
+    string_config(name = CONFIG_ICON_URL),
+    integer_config(name = CONFIG_MAX_MESSAGE_LENGTH, default = mrkdwn::MAX_MESSAGE_LENGTH as i64),
+    boolean_config(name = CONFIG_RENDER_BLOCKS),
+    integer_config(name = CONFIG_MAX_RETRIES, default = 3),
+    string_config(name = CONFIG_USERGROUP),
+    boolean_config(name = CONFIG_AUTO_REPLY_IN_THREAD),
+    string_config(name = CONFIG_IMAGE_FORMAT, default = "png"),
+    integer_config(name = CONFIG_IMAGE_QUALITY, default = 80),
+    integer_config(name = CONFIG_SNIPPET_THRESHOLD),
     custom_global_config(name = CONFIG_SLACK_BOT_TOKEN, type_ = "password", default = AgentValue::string(""), title = "Slack Bot Token"),
 )]
 struct SlackPostAgent {
     data: AgentData,
+    input_channel: Option<String>,
+    usergroup: Option<(String, String)>,
 }
 
 #[async_trait]
@@ -103,224 +228,608 @@ impl AsAgent for SlackPostAgent {
     fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
         Ok(Self {
             data: AgentData::new(ma, id, spec),
+            input_channel: None,
+            usergroup: None,
         })
     }
 
     async fn process(
         &mut self,
         ctx: AgentContext,
-        _port: String,
+        port: String,
         value: AgentValue,
     ) -> Result<(), AgentError> {
+        if port == PORT_CHANNEL {
+            self.input_channel = channel_from_value(&value);
+            return Ok(());
+        }
+
+        let content = extract_message_content(&value)?;
+        let usergroup = match self.configured_usergroup().await {
+            Ok(usergroup) => usergroup,
+            Err(e) => return self.output_error(ctx, None, e).await,
+        };
+
         let config = self.configs()?;
-        let channel = config.get_string(CONFIG_CHANNEL)?;
+        let channel = content
+            .channel
+            .clone()
+            .or_else(|| self.input_channel.clone())
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
         if channel.is_empty() {
             return Err(AgentError::InvalidValue(
                 "Channel not configured".to_string(),
             ));
         }
         let convert = config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true);
+        let mut mrkdwn_options = mrkdwn_options(config);
+        if let Some((handle, id)) = &usergroup {
+            mrkdwn_options.usergroups.insert(handle.clone(), id.clone());
+        }
+        let include_permalink = config.get_bool_or_default(CONFIG_INCLUDE_PERMALINK);
+        // A per-message `also_send_to_channel` wins over everything else
+        let reply_broadcast = content
+            .also_send_to_channel
+            .or(content.reply_broadcast)
+            .unwrap_or_else(|| config.get_bool_or_default(CONFIG_REPLY_BROADCAST));
 
         let token = get_token(self.ma())?;
         let client = get_client();
         let session = client.open_session(&token);
-        let channel_id: SlackChannelId = channel.clone().into();
-
-        // Handle image upload
-        #[cfg(feature = "image")]
-        if let Some(image) = value.as_image() {
-            let result = upload_image_to_slack(&session, image, &channel_id, None, None).await?;
-            return self.output(ctx, PORT_RESULT, result).await;
-        }
+        let channel_id = match slack::resolve_channel_id(&session, &channel).await {
+            Ok(channel_id) => channel_id,
+            Err(e) => {
+                let channel = SlackChannelId::new(channel);
+                return self.output_error(ctx, Some(&channel), e).await;
+            }
+        };
 
-        // Handle Message with image
+        // Handle images, a Message with an image, or an array of them
         #[cfg(feature = "image")]
-        if let Some(msg) = value.as_message()
-            && let Some(ref image) = msg.image
         {
-            let initial_comment = if msg.content.is_empty() {
-                None
-            } else if convert {
-                Some(mrkdwn::md_to_mrkdwn(&msg.content))
-            } else {
-                Some(msg.content.clone())
-            };
-            let result =
-                upload_image_to_slack(&session, image, &channel_id, initial_comment, None).await?;
-            return self.output(ctx, PORT_RESULT, result).await;
+            let images = value_images(&value);
+            if !images.is_empty() {
+                let initial_comment = if value.is_image() || content.text.is_empty() {
+                    None
+                } else {
+                    Some(message_text(content.text.clone(), convert, &mrkdwn_options))
+                };
+                let format = ImageFormat::from_config(config)?;
+                let result = upload_images_to_slack(
+                    &session,
+                    images,
+                    format,
+                    &channel_id,
+                    initial_comment,
+                    None,
+                )
+                .await;
+                return self.output_result(ctx, &channel_id, result).await;
+            }
         }
 
-        let (text, blocks, thread_ts) = extract_message_content(&value)?;
-        let text = if convert {
-            mrkdwn::md_to_mrkdwn(&text)
+        let MessageContent {
+            text,
+            blocks: blocks_value,
+            thread_ts,
+            ts,
+            username,
+            icon_emoji,
+            icon_url,
+            metadata,
+            ..
+        } = content;
+        // A message from the Listener carries its own ts; reply in its thread
+        let thread_ts = if config.get_bool_or_default(CONFIG_AUTO_REPLY_IN_THREAD) {
+            thread_ts.or(ts)
         } else {
-            text
+            thread_ts
         };
+        let slack_blocks = value_to_blocks(blocks_value.as_ref(), &mrkdwn_options)?;
+        let render_blocks =
+            slack_blocks.is_none() && config.get_bool_or_default(CONFIG_RENDER_BLOCKS);
 
-        let content = SlackMessageContent::new().with_text(text);
+        // Take very long code blocks out of the text to upload them as snippets
+        let snippet_threshold = config.get_integer_or_default(CONFIG_SNIPPET_THRESHOLD);
+        let (text, snippets) = if slack_blocks.is_none() && snippet_threshold > 0 {
+            mrkdwn::extract_code_snippets(&text, snippet_threshold as usize)
+        } else {
+            (text, Vec::new())
+        };
+        let only_snippets = !snippets.is_empty() && text.trim().is_empty();
 
-        let mut request = SlackApiChatPostMessageRequest::new(channel_id, content);
+        // Mention the configured user group first unless the text already does
+        let text = match &usergroup {
+            Some((handle, _)) if text.contains(&format!("@{}", handle)) => text,
+            Some((handle, _)) if convert || render_blocks => format!("@{}\n\n{}", handle, text),
+            Some((_, id)) => format!("<!subteam^{}>\n\n{}", id, text),
+            None => text,
+        };
 
-        if let Some(ts) = thread_ts {
-            request = request.with_thread_ts(ts.into());
-        }
+        let post_options = slack::PostOptions {
+            thread_ts: thread_ts.clone(),
+            reply_broadcast,
+            unfurl_links: Some(config.get_bool_or_default(CONFIG_UNFURL_LINKS)),
+            unfurl_media: Some(config.get_bool_or(CONFIG_UNFURL_MEDIA, true)),
+            username: username.or_else(|| non_empty_config(config, CONFIG_USERNAME)),
+            icon_emoji: icon_emoji.or_else(|| non_empty_config(config, CONFIG_ICON_EMOJI)),
+            icon_url: icon_url.or_else(|| non_empty_config(config, CONFIG_ICON_URL)),
+            metadata: metadata.as_ref().map(value_to_metadata).transpose()?,
+            max_retries: config.get_integer_or(CONFIG_MAX_RETRIES, 0).max(0) as u32,
+        };
+
+        let max_len = config.get_integer_or(CONFIG_MAX_MESSAGE_LENGTH, 0).max(0) as usize;
+        let messages = build_messages(
+            text,
+            slack_blocks,
+            render_blocks,
+            convert,
+            &mrkdwn_options,
+            max_len,
+        );
+
+        let result = if only_snippets {
+            // No message carries the mention, so the snippet upload comment does
+            let mention = usergroup.map(|(_, id)| format!("<!subteam^{}>", id));
+            upload_snippets(&session, &channel_id, snippets, thread_ts, mention, None).await
+        } else {
+            let result = post_messages(
+                &session,
+                &channel_id,
+                messages,
+                post_options,
+                include_permalink,
+            )
+            .await;
+            match result {
+                Ok(mut result) if !snippets.is_empty() => {
+                    // Attach the snippets in the thread of the posted message. The
+                    // message is already out, so a failed upload is only reported.
+                    let thread_ts = thread_ts.or_else(|| result.get_str("ts").map(String::from));
+                    match upload_snippets(
+                        &session,
+                        &channel_id,
+                        snippets,
+                        thread_ts,
+                        None,
+                        Some(result.clone()),
+                    )
+                    .await
+                    {
+                        Ok(result) => Ok(result),
+                        Err(e) => {
+                            error!("Failed to upload code snippets: {}", e);
+                            result.set("snippet_error".into(), slack_error_value(&e, None))?;
+                            Ok(result)
+                        }
+                    }
+                }
+                other => other,
+            }
+        };
+        self.output_result(ctx, &channel_id, result).await
+    }
+}
+
+/// Uploads code snippets as text files in `thread_ts` with an optional comment,
+/// adding their IDs to `result` as `file_ids`.
+async fn upload_snippets(
+    session: &slack::Session<'_>,
+    channel_id: &SlackChannelId,
+    snippets: Vec<mrkdwn::CodeSnippet>,
+    thread_ts: Option<String>,
+    initial_comment: Option<String>,
+    result: Option<AgentValue>,
+) -> Result<AgentValue, slack::SlackError> {
+    let files = snippets
+        .into_iter()
+        .enumerate()
+        .map(|(i, snippet)| slack::FileUpload {
+            bytes: snippet.code.into_bytes(),
+            filename: format!("snippet_{}.txt", i + 1),
+            content_type: "text/plain".to_string(),
+            title: Some(format!("Code snippet {}", i + 1)),
+            snippet_type: snippet.language,
+            ..Default::default()
+        })
+        .collect();
+    let file_ids =
+        slack::upload_files(session, files, channel_id, initial_comment, thread_ts).await?;
+
+    let mut result = result.unwrap_or_else(|| {
+        AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+        })
+    });
+    result.set(
+        "file_ids".into(),
+        AgentValue::array(file_ids.into_iter().map(AgentValue::string).collect()),
+    )?;
+    Ok(result)
+}
 
-        if let Some(blocks_value) = blocks
-            && let Ok(blocks_json) = serde_json::to_string(&blocks_value.to_json())
-            && let Ok(slack_blocks) = serde_json::from_str::<Vec<SlackBlock>>(&blocks_json)
+impl SlackPostAgent {
+    /// Resolves the configured `usergroup` handle to its ID via `usergroups.list`.
+    ///
+    /// The result is cached until the handle changes.
+    async fn configured_usergroup(
+        &mut self,
+    ) -> Result<Option<(String, String)>, slack::SlackError> {
+        let handle = self.configs()?.get_string_or_default(CONFIG_USERGROUP);
+        let handle = handle.trim().trim_start_matches('@');
+        if handle.is_empty() {
+            return Ok(None);
+        }
+        if let Some(cached) = &self.usergroup
+            && cached.0 == handle
         {
-            let content_with_blocks = SlackMessageContent::new()
-                .with_text(request.content.text.unwrap_or_default())
-                .with_blocks(slack_blocks);
-            request = SlackApiChatPostMessageRequest::new(request.channel, content_with_blocks);
+            return Ok(Some(cached.clone()));
+        }
+
+        let token = get_token(self.ma())?;
+        let session = get_client().open_session(&token);
+        let group = slack::list_usergroups(&session)
+            .await?
+            .into_iter()
+            .find(|g| g.handle == handle)
+            .ok_or_else(|| {
+                AgentError::InvalidConfig(format!("Usergroup not found: @{}", handle))
+            })?;
+
+        let resolved = (handle.to_string(), group.id.to_string());
+        self.usergroup = Some(resolved.clone());
+        Ok(Some(resolved))
+    }
+
+    /// Outputs `result`, or sends a failed Slack call to the `error` port.
+    async fn output_result(
+        &mut self,
+        ctx: AgentContext,
+        channel_id: &SlackChannelId,
+        result: Result<AgentValue, slack::SlackError>,
+    ) -> Result<(), AgentError> {
+        match result {
+            Ok(value) => self.output(ctx, PORT_RESULT, value).await,
+            Err(e) => self.output_error(ctx, Some(channel_id), e).await,
         }
+    }
 
-        let response = session
-            .chat_post_message(&request)
+    /// Sends a failed Slack call to the `error` port so the flow can fall back
+    /// instead of aborting.
+    async fn output_error(
+        &mut self,
+        ctx: AgentContext,
+        channel_id: Option<&SlackChannelId>,
+        err: slack::SlackError,
+    ) -> Result<(), AgentError> {
+        error!("Failed to post to Slack: {}", err);
+        self.output(ctx, PORT_ERROR, slack_error_value(&err, channel_id))
             .await
-            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    }
+}
 
-        let result = AgentValue::object(hashmap! {
-            "ok".into() => AgentValue::boolean(true),
-            "ts".into() => AgentValue::string(response.ts.to_string()),
-            "channel".into() => AgentValue::string(response.channel.to_string()),
-        });
+/// Turns the text (or given blocks) into the messages the Post agent sends, as
+/// `(text, blocks)` pairs.
+///
+/// Long text is split into several messages, and rendered blocks are spread over
+/// messages of at most [`blocks::MAX_BLOCKS`] blocks.
+fn build_messages(
+    text: String,
+    slack_blocks: Option<Vec<SlackBlock>>,
+    render_blocks: bool,
+    convert: bool,
+    mrkdwn_options: &mrkdwn::MrkdwnOptions,
+    max_len: usize,
+) -> Vec<(String, Option<Vec<SlackBlock>>)> {
+    if render_blocks {
+        return blocks::md_to_blocks(&text, mrkdwn_options)
+            .chunks(blocks::MAX_BLOCKS)
+            .map(|batch| (blocks::fallback_text(batch), Some(batch.to_vec())))
+            .collect();
+    }
 
-        self.output(ctx, PORT_RESULT, result).await
+    let text = message_text(text, convert, mrkdwn_options);
+    if slack_blocks.is_some() {
+        vec![(text, slack_blocks)]
+    } else {
+        mrkdwn::split_mrkdwn(&text, max_len)
+            .into_iter()
+            .map(|chunk| (chunk, None))
+            .collect()
     }
 }
 
-#[cfg(feature = "image")]
-async fn upload_image_to_slack(
-    session: &SlackClientSession<'_, HyperConnector>,
-    image: &PhotonImage,
+/// Posts `messages` in order; every message after the first goes to the thread of
+/// the first one.
+async fn post_messages(
+    session: &slack::Session<'_>,
     channel_id: &SlackChannelId,
-    initial_comment: Option<String>,
-    thread_ts: Option<String>,
-) -> Result<AgentValue, AgentError> {
-    use slack_morphism::api::{
-        SlackApiFilesComplete, SlackApiFilesCompleteUploadExternalRequest,
-        SlackApiFilesGetUploadUrlExternalRequest, SlackApiFilesUploadViaUrlRequest,
-    };
+    messages: Vec<(String, Option<Vec<SlackBlock>>)>,
+    post_options: slack::PostOptions,
+    include_permalink: bool,
+) -> Result<AgentValue, slack::SlackError> {
+    let mut messages = messages.into_iter();
+    let (first_text, first_blocks) = messages.next().unwrap_or_default();
+    let response =
+        slack::post_message(session, channel_id, first_text, first_blocks, &post_options).await?;
 
-    // Convert image to PNG bytes
-    let png_bytes = image.get_bytes();
-    let filename = format!("image_{}.png", chrono::Utc::now().timestamp_millis());
+    // Continuation chunks go to the same thread as the first message
+    let mut ts_list = vec![AgentValue::string(response.ts.to_string())];
+    let continuation_options = slack::PostOptions {
+        thread_ts: Some(
+            post_options
+                .thread_ts
+                .clone()
+                .unwrap_or_else(|| response.ts.to_string()),
+        ),
+        reply_broadcast: false,
+        metadata: None,
+        ..post_options
+    };
+    for (chunk_text, chunk_blocks) in messages {
+        let reply = slack::post_message(
+            session,
+            channel_id,
+            chunk_text,
+            chunk_blocks,
+            &continuation_options,
+        )
+        .await?;
+        ts_list.push(AgentValue::string(reply.ts.to_string()));
+    }
 
-    // Step 1: Get upload URL
-    let upload_url_request =
-        SlackApiFilesGetUploadUrlExternalRequest::new(filename.clone(), png_bytes.len());
+    let mut result = hashmap! {
+        "ok".into() => AgentValue::boolean(true),
+        "ts".into() => AgentValue::string(response.ts.to_string()),
+        "channel".into() => AgentValue::string(response.channel.to_string()),
+        "ts_list".into() => AgentValue::array(ts_list.into()),
+    };
 
-    let upload_url_response = session
-        .get_upload_url_external(&upload_url_request)
-        .await
-        .map_err(|e| AgentError::IoError(format!("Failed to get upload URL: {}", e)))?;
+    // The message is already posted, so a permalink failure only drops the field
+    if include_permalink {
+        match slack::get_permalink(session, &response.channel, &response.ts).await {
+            Ok(permalink) => {
+                result.insert("permalink".into(), AgentValue::string(permalink));
+            }
+            Err(e) => error!("Failed to get permalink: {}", e),
+        }
+    }
 
-    // Step 2: Upload file content
-    let upload_request = SlackApiFilesUploadViaUrlRequest::new(
-        upload_url_response.upload_url,
-        png_bytes,
-        "image/png".to_string(),
-    );
+    Ok(AgentValue::object(result))
+}
 
-    session
-        .files_upload_via_url(&upload_request)
-        .await
-        .map_err(|e| AgentError::IoError(format!("Failed to upload file: {}", e)))?;
+fn non_empty_config(config: &AgentConfigs, key: &str) -> Option<String> {
+    Some(config.get_string_or_default(key)).filter(|v| !v.is_empty())
+}
 
-    // Step 3: Complete upload
-    let file_complete = SlackApiFilesComplete::new(upload_url_response.file_id.clone());
-    let mut complete_request = SlackApiFilesCompleteUploadExternalRequest::new(vec![file_complete])
-        .with_channel_id(channel_id.clone());
+fn mrkdwn_options(config: &AgentConfigs) -> mrkdwn::MrkdwnOptions {
+    let date_format = config.get_string_or_default(CONFIG_DATE_FORMAT);
+    let special_mentions = if config.get_bool_or_default(CONFIG_ESCAPE_SPECIAL_MENTIONS) {
+        mrkdwn::SpecialMentions::Escape
+    } else if config.get_bool_or_default(CONFIG_CONVERT_SPECIAL_MENTIONS) {
+        mrkdwn::SpecialMentions::Convert
+    } else {
+        mrkdwn::SpecialMentions::Keep
+    };
+    mrkdwn::MrkdwnOptions {
+        date_format: (!date_format.is_empty()).then_some(date_format),
+        special_mentions,
+        ..Default::default()
+    }
+}
 
-    if let Some(comment) = initial_comment {
-        complete_request = complete_request.with_initial_comment(comment);
+/// Converts message text to mrkdwn, or only escapes special mentions when
+/// `convert` is off and `options` asks for it.
+fn message_text(text: String, convert: bool, options: &mrkdwn::MrkdwnOptions) -> String {
+    if convert {
+        mrkdwn::md_to_mrkdwn_with_options(&text, options)
+    } else if options.special_mentions == mrkdwn::SpecialMentions::Escape {
+        mrkdwn::escape_special_mentions(&text)
+    } else {
+        text
     }
+}
 
-    if let Some(ts) = thread_ts {
-        complete_request = complete_request.with_thread_ts(ts.into());
+/// Describes a failed Slack call as `{ok: false, error, message, channel}`.
+///
+/// `error` is the Slack error code such as `channel_not_found`, or `request_failed`
+/// when the request failed without one.
+fn slack_error_value(err: &slack::SlackError, channel_id: Option<&SlackChannelId>) -> AgentValue {
+    let code = err
+        .code
+        .clone()
+        .unwrap_or_else(|| "request_failed".to_string());
+    let mut obj = hashmap! {
+        "ok".into() => AgentValue::boolean(false),
+        "error".into() => AgentValue::string(code),
+        "message".into() => AgentValue::string(err.to_string()),
+    };
+    if let Some(channel_id) = channel_id {
+        obj.insert("channel".into(), AgentValue::string(channel_id.to_string()));
     }
+    AgentValue::object(obj)
+}
 
-    let complete_response = session
-        .files_complete_upload_external(&complete_request)
-        .await
-        .map_err(|e| AgentError::IoError(format!("Failed to complete upload: {}", e)))?;
+/// An image to upload, with the optional `filename`, `title` and `alt_text` given for it.
+#[cfg(feature = "image")]
+struct ImageUpload<'a> {
+    image: &'a PhotonImage,
+    filename: Option<String>,
+    title: Option<String>,
+    alt_text: Option<String>,
+}
 
-    let file_id = complete_response
-        .files
-        .first()
-        .map(|f| f.id.to_string())
-        .unwrap_or_default();
+/// Collects the images of an image value, a Message with an image, an object with
+/// `image` (and `filename`, `title`, `alt_text`), or an array of them.
+#[cfg(feature = "image")]
+fn value_images(value: &AgentValue) -> Vec<ImageUpload<'_>> {
+    let non_empty = |key: &str| {
+        value
+            .get_str(key)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+    };
+    match value {
+        AgentValue::Array(arr) => arr.iter().flat_map(value_images).collect(),
+        AgentValue::Object(_) => value
+            .get_image("image")
+            .map(|image| ImageUpload {
+                image,
+                filename: non_empty("filename"),
+                title: non_empty("title"),
+                alt_text: non_empty("alt_text"),
+            })
+            .into_iter()
+            .collect(),
+        _ => value
+            .as_image()
+            .or_else(|| value.as_message().and_then(|m| m.image.as_deref()))
+            .map(|image| ImageUpload {
+                image,
+                filename: None,
+                title: None,
+                alt_text: None,
+            })
+            .into_iter()
+            .collect(),
+    }
+}
 
-    Ok(AgentValue::object(hashmap! {
-        "ok".into() => AgentValue::boolean(true),
-        "file_id".into() => AgentValue::string(file_id),
-        "channel".into() => AgentValue::string(channel_id.to_string()),
-    }))
+/// Encoding used for uploaded images.
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, Debug)]
+enum ImageFormat {
+    Png,
+    /// JPEG with the given quality (1-100).
+    Jpeg(u8),
+    Webp,
 }
 
-fn extract_message_content(
-    value: &AgentValue,
-) -> Result<(String, Option<AgentValue>, Option<String>), AgentError> {
-    match value {
-        AgentValue::String(s) => Ok((s.to_string(), None, None)),
-        AgentValue::Message(msg) => Ok((msg.content.clone(), None, None)),
-        AgentValue::Object(obj) => {
-            let text = obj
-                .get("text")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            let blocks = obj.get("blocks").cloned();
-            let thread_ts = obj
-                .get("thread_ts")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-            Ok((text, blocks, thread_ts))
+#[cfg(feature = "image")]
+impl ImageFormat {
+    /// Reads the `image_format` and `image_quality` configs.
+    fn from_config(config: &AgentConfigs) -> Result<Self, AgentError> {
+        let format = config.get_string_or_default(CONFIG_IMAGE_FORMAT);
+        match format.trim().to_lowercase().as_str() {
+            "" | "png" => Ok(Self::Png),
+            "jpeg" | "jpg" => {
+                let quality = config
+                    .get_integer_or(CONFIG_IMAGE_QUALITY, 80)
+                    .clamp(1, 100);
+                Ok(Self::Jpeg(quality as u8))
+            }
+            "webp" => Ok(Self::Webp),
+            other => Err(AgentError::InvalidConfig(format!(
+                "Unknown image_format: {} (expected png, jpeg or webp)",
+                other
+            ))),
         }
-        AgentValue::Array(arr) => {
-            let texts: Vec<String> = arr
-                .iter()
-                .filter_map(|v| {
-                    v.as_str()
-                        .map(String::from)
-                        .or_else(|| v.as_message().map(|m| m.content.clone()))
-                })
-                .collect();
-            Ok((texts.join("\n"), None, None))
+    }
+
+    fn encode(self, image: &PhotonImage) -> Vec<u8> {
+        match self {
+            Self::Png => image.get_bytes(),
+            Self::Jpeg(quality) => image.get_bytes_jpeg(quality),
+            Self::Webp => image.get_bytes_webp(),
         }
-        _ => {
-            let json = serde_json::to_string_pretty(&value.to_json()).unwrap_or_default();
-            Ok((format!("```\n{}\n```", json), None, None))
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg(_) => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg(_) => "image/jpeg",
+            Self::Webp => "image/webp",
         }
     }
 }
 
-/// Agent for fetching message history from a Slack channel.
+/// Uploads images encoded as `format`, grouped in a single message.
+#[cfg(feature = "image")]
+async fn upload_images_to_slack(
+    session: &slack::Session<'_>,
+    images: Vec<ImageUpload<'_>>,
+    format: ImageFormat,
+    channel_id: &SlackChannelId,
+    initial_comment: Option<String>,
+    thread_ts: Option<String>,
+) -> Result<AgentValue, slack::SlackError> {
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let count = images.len();
+    let files = images
+        .into_iter()
+        .enumerate()
+        .map(|(i, upload)| slack::FileUpload {
+            bytes: format.encode(upload.image),
+            filename: upload.filename.unwrap_or_else(|| {
+                if count == 1 {
+                    format!("image_{}.{}", timestamp, format.extension())
+                } else {
+                    format!("image_{}_{}.{}", timestamp, i + 1, format.extension())
+                }
+            }),
+            content_type: format.content_type().to_string(),
+            title: upload.title,
+            alt_text: upload.alt_text,
+            ..Default::default()
+        })
+        .collect();
+
+    let file_ids =
+        slack::upload_files(session, files, channel_id, initial_comment, thread_ts).await?;
+
+    Ok(AgentValue::object(hashmap! {
+        "ok".into() => AgentValue::boolean(true),
+        "file_id".into() => AgentValue::string(file_ids.first().cloned().unwrap_or_default()),
+        "file_ids".into() => AgentValue::array(file_ids.into_iter().map(AgentValue::string).collect()),
+        "channel".into() => AgentValue::string(channel_id.to_string()),
+    }))
+}
+
+/// Agent for posting messages through an incoming webhook.
+///
+/// Needs no bot token; the channel is fixed by the webhook.
 ///
 /// # Configuration
-/// - `channel`: The Slack channel name or ID to fetch history from
-/// - `limit`: Maximum number of messages to fetch (default: 10)
+/// - `webhook_url`: Incoming webhook URL
+/// - `convert_markdown`: Convert Markdown/HTML to Slack mrkdwn (default: true)
+/// - `date_format`: Slack date format for ISO timestamps (see Post)
+/// - `convert_special_mentions`, `escape_special_mentions`: Special mention handling
+///   (see Post)
 ///
 /// # Input
-/// - `trigger`: Any value triggers fetching the history
+/// - `message`: String message or object with `text` and `blocks` fields
 ///
 /// # Output
-/// - `values`: Array of Slack message objects containing `text`, `user`, `ts`, etc.
+/// - `result`: Object containing `ok` on success
+/// - `error`: Object containing `ok` (false), `error` and `message` when the post fails
+///   for any reason, e.g. `invalid_payload`, `no_service`, or `request_failed` for
+///   an HTTP error without a code or an invalid webhook URL
 #[modular_agent(
-    title = "History",
+    title = "WebhookPost",
     category = CATEGORY,
-    inputs = [PORT_TRIGGER],
-    outputs = [PORT_VALUES],
-    string_config(name = CONFIG_CHANNEL),
-    integer_config(name = CONFIG_LIMIT),
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT, PORT_ERROR],
+    custom_config(name = CONFIG_WEBHOOK_URL, type_ = "password", default = AgentValue::string(""), title = "Webhook URL"),
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
 )]
-struct SlackHistoryAgent {
+struct SlackWebhookPostAgent {
     data: AgentData,
 }
 
 #[async_trait]
-impl AsAgent for SlackHistoryAgent {
+impl AsAgent for SlackWebhookPostAgent {
     fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
         Ok(Self {
             data: AgentData::new(ma, id, spec),
@@ -331,91 +840,77 @@ impl AsAgent for SlackHistoryAgent {
         &mut self,
         ctx: AgentContext,
         _port: String,
-        _value: AgentValue,
+        value: AgentValue,
     ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
         let config = self.configs()?;
-        let channel = config.get_string(CONFIG_CHANNEL)?;
-        if channel.is_empty() {
-            return Err(AgentError::InvalidValue(
-                "Channel not configured".to_string(),
+        let Some(webhook_url) = non_empty_config(config, CONFIG_WEBHOOK_URL) else {
+            return Err(AgentError::InvalidConfig(
+                "Webhook URL not configured".to_string(),
             ));
-        }
-
-        let token = get_token(self.ma())?;
-        let limit = config.get_integer_or_default(CONFIG_LIMIT);
-        let limit = if limit <= 0 { 10 } else { limit as u16 };
-
-        let client = get_client();
-        let session = client.open_session(&token);
-
-        let channel_id: SlackChannelId = channel.into();
-        let request = SlackApiConversationsHistoryRequest::new()
-            .with_channel(channel_id)
-            .with_limit(limit);
-
-        let response = session
-            .conversations_history(&request)
-            .await
-            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
-
-        let messages: Vector<AgentValue> = response
-            .messages
-            .iter()
-            .map(slack_message_to_agent_value)
-            .collect();
-
-        self.output(ctx, PORT_VALUES, AgentValue::array(messages))
-            .await
-    }
-}
-
-fn slack_message_to_agent_value(msg: &SlackHistoryMessage) -> AgentValue {
-    let mut obj = im::HashMap::new();
-
-    // SlackHistoryMessage uses #[serde(flatten)] so fields are directly accessible
-    if let Some(text) = &msg.content.text {
-        obj.insert("text".into(), AgentValue::string(text.clone()));
-    }
-
-    if let Some(user) = &msg.sender.user {
-        obj.insert("user".into(), AgentValue::string(user.to_string()));
-    }
-
-    obj.insert("ts".into(), AgentValue::string(msg.origin.ts.to_string()));
-
-    if let Some(thread_ts) = &msg.origin.thread_ts {
-        obj.insert(
-            "thread_ts".into(),
-            AgentValue::string(thread_ts.to_string()),
+        };
+        let options = mrkdwn_options(config);
+        let text = message_text(
+            content.text,
+            config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true),
+            &options,
         );
-    }
+        let slack_blocks = value_to_blocks(content.blocks.as_ref(), &options)?;
 
-    AgentValue::object(obj)
+        match slack::post_webhook_message(&webhook_url, text, slack_blocks).await {
+            Ok(()) => {
+                let result = hashmap! {
+                    "ok".into() => AgentValue::boolean(true),
+                };
+                self.output(ctx, PORT_RESULT, AgentValue::object(result))
+                    .await
+            }
+            Err(e) => {
+                error!("Failed to post to Slack webhook: {}", e);
+                self.output(ctx, PORT_ERROR, slack_error_value(&e, None))
+                    .await
+            }
+        }
+    }
 }
 
-/// Agent for listing Slack channels.
+/// Agent for sending direct messages to a user.
+///
+/// The user can be given as an ID or an email address; the DM channel is opened
+/// with `conversations.open`, so no D-channel ID is needed.
 ///
 /// # Configuration
-/// - `limit`: Maximum number of channels to fetch (default: 100)
+/// - `user`: Default user ID or email address, used when the input has no `user`
+/// - `convert_markdown`: Convert Markdown/HTML to Slack mrkdwn (default: true)
+/// - `date_format`: Slack date format for ISO timestamps (see Post)
+/// - `convert_special_mentions`, `escape_special_mentions`: Special mention handling
+///   (see Post)
 ///
 /// # Input
-/// - `trigger`: Any value triggers fetching the channel list
+/// - `message`: String message or object with `text`, `blocks`, `thread_ts`, `user` fields
 ///
 /// # Output
-/// - `channels`: Array of channel objects containing `id`, `name`, `is_private`, etc.
+/// - `result`: Object containing `ok`, `ts`, `channel`, `ts_list`, `user` on success
+/// - `error`: Object containing `ok` (false), `error` and `message` when Slack rejects
+///   the lookup or the post
 #[modular_agent(
-    title = "Channels",
+    title = "DM",
     category = CATEGORY,
-    inputs = [PORT_TRIGGER],
-    outputs = [PORT_CHANNELS],
-    integer_config(name = CONFIG_LIMIT),
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT, PORT_ERROR],
+    string_config(name = CONFIG_USER),
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
 )]
-struct SlackChannelsAgent {
+struct SlackDmAgent {
     data: AgentData,
 }
 
 #[async_trait]
-impl AsAgent for SlackChannelsAgent {
+impl AsAgent for SlackDmAgent {
     fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
         Ok(Self {
             data: AgentData::new(ma, id, spec),
@@ -426,245 +921,5702 @@ impl AsAgent for SlackChannelsAgent {
         &mut self,
         ctx: AgentContext,
         _port: String,
-        _value: AgentValue,
+        value: AgentValue,
     ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
         let config = self.configs()?;
+        let user = content
+            .user
+            .clone()
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_USER));
+        if user.is_empty() {
+            return Err(AgentError::InvalidValue("User not configured".to_string()));
+        }
+        let options = mrkdwn_options(config);
+        let text = message_text(
+            content.text,
+            config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true),
+            &options,
+        );
+        let slack_blocks = value_to_blocks(content.blocks.as_ref(), &options)?;
+        let messages = if slack_blocks.is_some() {
+            vec![(text, slack_blocks)]
+        } else {
+            mrkdwn::split_mrkdwn(&text, mrkdwn::MAX_MESSAGE_LENGTH)
+                .into_iter()
+                .map(|chunk| (chunk, None))
+                .collect()
+        };
+        let post_options = slack::PostOptions {
+            thread_ts: content.thread_ts,
+            ..Default::default()
+        };
+
         let token = get_token(self.ma())?;
-        let limit = config.get_integer_or_default(CONFIG_LIMIT);
-        let limit = if limit <= 0 { 100 } else { limit as u16 };
-
         let client = get_client();
         let session = client.open_session(&token);
 
-        let request = SlackApiConversationsListRequest::new().with_limit(limit);
+        let result = async {
+            let user_id = slack::resolve_user_id(&session, &user).await?;
+            let channel_id = slack::open_dm(&session, &user_id).await?;
+            let mut result =
+                post_messages(&session, &channel_id, messages, post_options, false).await?;
+            if let Some(obj) = result.as_object_mut() {
+                obj.insert("user".into(), AgentValue::string(user_id.to_string()));
+            }
+            Ok::<_, slack::SlackError>(result)
+        }
+        .await;
 
-        let response = session
-            .conversations_list(&request)
-            .await
-            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+        match result {
+            Ok(result) => self.output(ctx, PORT_RESULT, result).await,
+            Err(e) => {
+                error!("Failed to send DM to {}: {}", user, e);
+                self.output(ctx, PORT_ERROR, slack_error_value(&e, None))
+                    .await
+            }
+        }
+    }
+}
 
-        let channels: Vector<AgentValue> = response
-            .channels
-            .iter()
-            .map(slack_channel_to_agent_value)
-            .collect();
+/// Agent for previewing what the Post agent would send, without calling Slack.
+///
+/// Runs the same Markdown conversion, block rendering and splitting as Post.
+///
+/// # Configuration
+/// - `convert_markdown`, `date_format`, `convert_special_mentions`,
+///   `escape_special_mentions`, `render_blocks`, `max_message_length`: Same as Post
+///
+/// # Input
+/// - `message`: Same as Post
+///
+/// # Output
+/// - `value`: Array of message payloads, each with `text` and, when present,
+///   `blocks`, `channel` and `thread_ts`
+#[modular_agent(
+    title = "Preview",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_VALUE],
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_RENDER_BLOCKS),
+    integer_config(name = CONFIG_MAX_MESSAGE_LENGTH, default = mrkdwn::MAX_MESSAGE_LENGTH as i64),
+)]
+struct SlackPreviewAgent {
+    data: AgentData,
+}
 
-        self.output(ctx, PORT_CHANNELS, AgentValue::array(channels))
+#[async_trait]
+impl AsAgent for SlackPreviewAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let options = mrkdwn_options(config);
+        let slack_blocks = value_to_blocks(content.blocks.as_ref(), &options)?;
+        let render_blocks =
+            slack_blocks.is_none() && config.get_bool_or_default(CONFIG_RENDER_BLOCKS);
+        let max_len = config.get_integer_or(CONFIG_MAX_MESSAGE_LENGTH, 0).max(0) as usize;
+        let messages = build_messages(
+            content.text,
+            slack_blocks,
+            render_blocks,
+            config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true),
+            &options,
+            max_len,
+        );
+
+        let mut payloads = Vector::new();
+        for (text, blocks) in messages {
+            let mut payload = hashmap! {
+                "text".into() => AgentValue::string(text),
+            };
+            if let Some(blocks) = blocks {
+                payload.insert("blocks".into(), AgentValue::from_serialize(&blocks)?);
+            }
+            if let Some(channel) = &content.channel {
+                payload.insert("channel".into(), AgentValue::string(channel.clone()));
+            }
+            if let Some(thread_ts) = &content.thread_ts {
+                payload.insert("thread_ts".into(), AgentValue::string(thread_ts.clone()));
+            }
+            payloads.push_back(AgentValue::object(payload));
+        }
+
+        self.output(ctx, PORT_VALUE, AgentValue::array(payloads))
             .await
     }
 }
 
-fn slack_channel_to_agent_value(ch: &SlackChannelInfo) -> AgentValue {
-    let mut obj = im::HashMap::new();
-
-    obj.insert("id".into(), AgentValue::string(ch.id.to_string()));
+/// Agent for uploading arbitrary files (CSV, PDF, reports, ...) via the external upload flow.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `filename`: Default file name, used when the input has no `filename`
+/// - `convert_markdown`: Convert Markdown/HTML in `initial_comment` to Slack mrkdwn
+///   (default: true)
+/// - `date_format`, `convert_special_mentions`, `escape_special_mentions`: Same as Post,
+///   applied to `initial_comment`
+///
+/// # Input
+/// - `message`: Object with the content as `base64` or `bytes` (an array of byte values,
+///   or a string uploaded as UTF-8), and optionally `filename`, `mimetype`, `title`,
+///   `alt_text`, `channel`, `thread_ts`, `initial_comment`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `file_id`, `channel` on success
+#[modular_agent(
+    title = "FileUpload",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_FILENAME),
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
+)]
+struct SlackFileUploadAgent {
+    data: AgentData,
+}
 
-    if let Some(name) = &ch.name {
-        obj.insert("name".into(), AgentValue::string(name.clone()));
+#[async_trait]
+impl AsAgent for SlackFileUploadAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
     }
 
-    // Access flags fields directly (they are Option<bool>)
-    if let Some(is_private) = ch.flags.is_private {
-        obj.insert("is_private".into(), AgentValue::boolean(is_private));
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+        let bytes = file_bytes(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let non_empty = |key: &str| {
+            value
+                .get_str(key)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+        };
+        let filename = non_empty(CONFIG_FILENAME)
+            .or_else(|| Some(config.get_string_or_default(CONFIG_FILENAME)))
+            .filter(|f| !f.is_empty())
+            .unwrap_or_else(|| format!("file_{}", chrono::Utc::now().timestamp_millis()));
+        let convert = config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true);
+        let options = mrkdwn_options(config);
+        let initial_comment =
+            non_empty("initial_comment").map(|comment| message_text(comment, convert, &options));
+        let file = slack::FileUpload {
+            bytes,
+            filename,
+            content_type: non_empty("mimetype")
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+            title: non_empty("title"),
+            alt_text: non_empty("alt_text"),
+            ..Default::default()
+        };
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        let file_ids = slack::upload_files(
+            &session,
+            vec![file],
+            &channel_id,
+            initial_comment,
+            content.thread_ts,
+        )
+        .await?;
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "file_id".into() => AgentValue::string(file_ids.into_iter().next().unwrap_or_default()),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
     }
+}
 
-    if let Some(is_archived) = ch.flags.is_archived {
-        obj.insert("is_archived".into(), AgentValue::boolean(is_archived));
+/// Reads file content from the input's `base64` or `bytes`.
+fn file_bytes(value: &AgentValue) -> Result<Vec<u8>, AgentError> {
+    if let Some(encoded) = value.get_str("base64") {
+        return base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| AgentError::InvalidValue(format!("Invalid base64 content: {}", e)));
+    }
+    match value.get("bytes") {
+        Some(AgentValue::String(s)) => Ok(s.as_bytes().to_vec()),
+        Some(AgentValue::Array(arr)) => arr
+            .iter()
+            .map(|b| {
+                b.to_integer()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| {
+                        AgentError::InvalidValue("bytes must be values from 0 to 255".to_string())
+                    })
+            })
+            .collect(),
+        _ => Err(AgentError::InvalidValue(
+            "File content (bytes or base64) is required".to_string(),
+        )),
     }
+}
 
-    if let Some(is_member) = ch.flags.is_member {
-        obj.insert("is_member".into(), AgentValue::boolean(is_member));
+/// Agent for posting "/me"-style status lines via `chat.meMessage`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `convert_markdown`: Convert Markdown/HTML to Slack mrkdwn (default: true)
+/// - `date_format`, `convert_special_mentions`, `escape_special_mentions`: Same as Post
+///
+/// # Input
+/// - `message`: String message, or object with `text` and `channel`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `ts`, `channel` on success
+#[modular_agent(
+    title = "MeMessage",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
+)]
+struct SlackMeMessageAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackMeMessageAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
     }
 
-    if let Some(num_members) = ch.num_members {
-        obj.insert(
-            "num_members".into(),
-            AgentValue::integer(num_members as i64),
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let text = message_text(
+            content.text,
+            config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true),
+            &mrkdwn_options(config),
         );
-    }
+        if text.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Message text is required".to_string(),
+            ));
+        }
 
-    if let Some(ref topic) = ch.topic {
-        obj.insert("topic".into(), AgentValue::string(topic.value.clone()));
-    }
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
 
-    if let Some(ref purpose) = ch.purpose {
-        obj.insert("purpose".into(), AgentValue::string(purpose.value.clone()));
-    }
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let (channel_id, ts) = slack::post_me_message(&session, &channel_id, text).await?;
 
-    AgentValue::object(obj)
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "ts".into() => AgentValue::string(ts.to_string()),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
 }
 
-/// Agent for listening to Slack messages in real-time via Socket Mode.
+/// Agent for posting ephemeral messages visible only to one user.
 ///
-/// This agent starts listening when activated and outputs messages as they arrive.
+/// Uses the same Markdown conversion as the Post agent.
 ///
 /// # Configuration
-/// - `channel`: Optional channel filter. If empty, listens to all channels.
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `user`: Default user ID, used when the input has no `user`
+/// - `convert_markdown`: Convert Markdown/HTML to Slack mrkdwn (default: true)
+/// - `date_format`: Slack date format for ISO timestamps (see Post)
+/// - `convert_special_mentions`, `escape_special_mentions`: Special mention handling
+///   (see Post)
+///
+/// # Input
+/// - `message`: String message or object with `text`, `blocks`, `thread_ts`, `channel`, `user` fields
 ///
 /// # Output
-/// - `value`: Slack Message objects containing `text`, `user`, `channel`, `ts`, `thread_ts` fields
+/// - `result`: Object containing `ok`, `channel`, `user` on success
+#[modular_agent(
+    title = "PostEphemeral",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_USER),
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
+)]
+struct SlackPostEphemeralAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackPostEphemeralAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .clone()
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let user = content
+            .user
+            .clone()
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_USER));
+        if user.is_empty() {
+            return Err(AgentError::InvalidValue("User not configured".to_string()));
+        }
+        let convert = config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true);
+
+        let options = mrkdwn_options(config);
+        let text = message_text(content.text, convert, &options);
+        let slack_blocks = value_to_blocks(content.blocks.as_ref(), &options)?;
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let user_id: SlackUserId = user.into();
+
+        slack::post_ephemeral(
+            &session,
+            &channel_id,
+            &user_id,
+            text,
+            slack_blocks,
+            content.thread_ts,
+        )
+        .await?;
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "user".into() => AgentValue::string(user_id.to_string()),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for editing a previously posted message via `chat.update`.
 ///
-/// # Required Tokens
-/// - `SLACK_BOT_TOKEN`: Bot User OAuth Token (via global config or environment)
-/// - `SLACK_APP_TOKEN`: App-Level Token with `connections:write` scope (via global config or environment)
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `convert_markdown`: Convert Markdown/HTML to Slack mrkdwn (default: true)
+/// - `date_format`, `convert_special_mentions`, `escape_special_mentions`: Same as Post
+///
+/// # Input
+/// - `message`: Object with `channel`, `ts`, and the new `text` and/or `blocks`
+///   (e.g., the `result` of a Post agent merged with new text)
+///
+/// # Output
+/// - `result`: Object containing `ok`, `ts`, `channel` on success
 #[modular_agent(
-    title = "Listener",
+    title = "Update",
     category = CATEGORY,
-    outputs = [PORT_VALUE],
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
     string_config(name = CONFIG_CHANNEL),
-    custom_global_config(name = CONFIG_SLACK_APP_TOKEN, type_ = "password", default = AgentValue::string(""), title = "Slack App Token"),
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
 )]
-struct SlackListenerAgent {
+struct SlackUpdateAgent {
     data: AgentData,
-    shutdown_tx: Option<mpsc::Sender<()>>,
 }
 
-struct SlackListenerUserState {
-    ma: ModularAgent,
-    id: String,
-    channel_filter: Option<String>,
-    bot_user_id: SlackUserId,
-    bot_token: String,
+#[async_trait]
+impl AsAgent for SlackUpdateAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .clone()
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let Some(ts) = content.ts.clone().filter(|ts| !ts.is_empty()) else {
+            return Err(AgentError::InvalidValue(
+                "Message ts is required".to_string(),
+            ));
+        };
+        let convert = config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true);
+
+        let options = mrkdwn_options(config);
+        let text = message_text(content.text, convert, &options);
+        let slack_blocks = value_to_blocks(content.blocks.as_ref(), &options)?;
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        let response =
+            slack::update_message(&session, &channel_id, &ts.into(), text, slack_blocks).await?;
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "ts".into() => AgentValue::string(response.ts.to_string()),
+            "channel".into() => AgentValue::string(response.channel.to_string()),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for deleting a message via `chat.delete`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+///
+/// # Input
+/// - `message`: Object with `channel` and `ts` (e.g., the `result` of a Post agent)
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel`, `ts` on success
+///
+/// `message_not_found` and `cant_delete_message` are reported as invalid-value
+/// errors naming the Slack error code.
+#[modular_agent(
+    title = "Delete",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+)]
+struct SlackDeleteAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackDeleteAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let Some(ts) = content.ts.filter(|ts| !ts.is_empty()) else {
+            return Err(AgentError::InvalidValue(
+                "Message ts is required".to_string(),
+            ));
+        };
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        let response = slack::delete_message(&session, &channel_id, &ts.into()).await?;
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(response.channel.to_string()),
+            "ts".into() => AgentValue::string(response.ts.to_string()),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for removing an emoji reaction from a message via `reactions.remove`.
+///
+/// Only reactions added by the bot itself can be removed.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `name`: Default emoji name (e.g. `eyes`), used when the input has no `name`
+///
+/// # Input
+/// - `message`: Object with `channel`, `ts` and optionally `name`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel`, `ts`, `name` on success
+#[modular_agent(
+    title = "RemoveReaction",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_NAME),
+)]
+struct SlackRemoveReactionAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackRemoveReactionAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let Some(ts) = content.ts.filter(|ts| !ts.is_empty()) else {
+            return Err(AgentError::InvalidValue(
+                "Message ts is required".to_string(),
+            ));
+        };
+        let Some(name) = reaction_name(&value, config) else {
+            return Err(AgentError::InvalidValue(
+                "Reaction name is required".to_string(),
+            ));
+        };
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        slack::remove_reaction(&session, &channel_id, &ts.clone().into(), &name).await?;
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "ts".into() => AgentValue::string(ts),
+            "name".into() => AgentValue::string(name),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Reads the emoji name from the input's `name` or the `name` config, without colons.
+fn reaction_name(value: &AgentValue, config: &AgentConfigs) -> Option<String> {
+    let name = value
+        .get_str("name")
+        .map(String::from)
+        .unwrap_or_else(|| config.get_string_or_default(CONFIG_NAME));
+    Some(name.trim().trim_matches(':').to_string()).filter(|n| !n.is_empty())
+}
+
+/// Agent for fetching the reactions on a message via `reactions.get`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+///
+/// # Input
+/// - `message`: Object with `channel` and `ts`
+///
+/// # Output
+/// - `reactions`: Array of objects with `name`, `count` and `users` (user IDs)
+#[modular_agent(
+    title = "Reactions",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_REACTIONS],
+    string_config(name = CONFIG_CHANNEL),
+)]
+struct SlackReactionsAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackReactionsAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let Some(ts) = content.ts.filter(|ts| !ts.is_empty()) else {
+            return Err(AgentError::InvalidValue(
+                "Message ts is required".to_string(),
+            ));
+        };
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        let reactions = slack::get_reactions(&session, &channel_id, &ts.into()).await?;
+        let reactions: Vector<AgentValue> = reactions.iter().map(reaction_to_value).collect();
+
+        self.output(ctx, PORT_REACTIONS, AgentValue::array(reactions))
+            .await
+    }
+}
+
+fn reaction_to_value(reaction: &SlackReaction) -> AgentValue {
+    let users: Vector<AgentValue> = reaction
+        .users
+        .iter()
+        .map(|u| AgentValue::string(u.to_string()))
+        .collect();
+    AgentValue::object(hashmap! {
+        "name".into() => AgentValue::string(reaction.name.to_string()),
+        "count".into() => AgentValue::integer(reaction.count as i64),
+        "users".into() => AgentValue::array(users),
+    })
+}
+
+/// Agent for pinning or unpinning a message via `pins.add` / `pins.remove`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `action`: Default action, `add` or `remove`, used when the input has no `action`
+///
+/// # Input
+/// - `message`: Object with `channel`, `ts` and optionally `action`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel`, `ts`, `action` on success
+#[modular_agent(
+    title = "Pin",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_ACTION, default = "add"),
+)]
+struct SlackPinAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackPinAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let Some(ts) = content.ts.filter(|ts| !ts.is_empty()) else {
+            return Err(AgentError::InvalidValue(
+                "Message ts is required".to_string(),
+            ));
+        };
+        let action = value
+            .get_str(CONFIG_ACTION)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_ACTION));
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let slack_ts: SlackTs = ts.clone().into();
+
+        match action.as_str() {
+            "add" => slack::pin_message(&session, &channel_id, &slack_ts).await?,
+            "remove" => slack::unpin_message(&session, &channel_id, &slack_ts).await?,
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown pin action: {} (expected add or remove)",
+                    other
+                )));
+            }
+        }
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "ts".into() => AgentValue::string(ts),
+            "action".into() => AgentValue::string(action),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for listing the messages pinned in a channel via `pins.list`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no channel
+///
+/// # Input
+/// - `channel`: Channel name or ID, or an object with `channel`
+///
+/// # Output
+/// - `values`: Array of pinned message objects containing `text`, `user`, `ts`,
+///   `thread_ts`, `channel`, `files`, plus `pinned_by` and `pinned_at` (Unix time),
+///   most recently pinned first
+#[modular_agent(
+    title = "Pins",
+    category = CATEGORY,
+    inputs = [PORT_CHANNEL],
+    outputs = [PORT_VALUES],
+    string_config(name = CONFIG_CHANNEL),
+)]
+struct SlackPinsAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackPinsAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let channel = value_channel(&value, self.configs()?)?;
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let mut pins = slack::list_pins(&session, &channel_id).await?;
+        pins.sort_by_key(|pin| std::cmp::Reverse(pin.created.0));
+
+        let pins: Vector<AgentValue> = pins.iter().filter_map(pin_to_value).collect();
+        self.output(ctx, PORT_VALUES, AgentValue::array(pins)).await
+    }
+}
+
+fn pin_to_value(pin: &SlackPin) -> Option<AgentValue> {
+    let msg = pin.message.as_ref()?;
+    let mut obj = im::HashMap::new();
+    obj.insert(
+        "channel".into(),
+        AgentValue::string(pin.channel.to_string()),
+    );
+    obj.insert("ts".into(), AgentValue::string(msg.origin.ts.to_string()));
+    if let Some(thread_ts) = &msg.origin.thread_ts {
+        obj.insert(
+            "thread_ts".into(),
+            AgentValue::string(thread_ts.to_string()),
+        );
+    }
+    if let Some(text) = &msg.content.text {
+        obj.insert("text".into(), AgentValue::string(text.clone()));
+    }
+    if let Some(user) = &msg.sender.user {
+        obj.insert("user".into(), AgentValue::string(user.to_string()));
+    }
+    if let Some(files) = msg.content.files.as_ref().filter(|f| !f.is_empty()) {
+        obj.insert(
+            "files".into(),
+            AgentValue::array(files.iter().map(|f| file_to_value(f, None)).collect()),
+        );
+    }
+    obj.insert(
+        "pinned_by".into(),
+        AgentValue::string(pin.created_by.to_string()),
+    );
+    obj.insert(
+        "pinned_at".into(),
+        AgentValue::integer(pin.created.0.as_second()),
+    );
+    Some(AgentValue::object(obj))
+}
+
+/// Agent for managing reminders via `reminders.add` / `reminders.list` /
+/// `reminders.delete`.
+///
+/// Reminders belong to a user, so this agent uses the user token
+/// (`slack_user_token` global config of the Star agent or `SLACK_USER_TOKEN`).
+///
+/// # Configuration
+/// - `action`: Default action, used when the input has no `action`: `add` (default),
+///   `list` or `delete`
+/// - `time`: Default time for `add`, used when the input has no `time`
+///
+/// # Input
+/// - `value`: Reminder text, or an object with `action` and
+///   - for `add`: `text`, `time` (Unix time, seconds from now, or natural language such
+///     as `in 2 hours`) and optionally `user` (ID or email, default: the token's user)
+///   - for `delete`: `id`
+///
+/// # Output
+/// - `value`: The created reminder (`id`, `text`, `user`, `creator`, `recurring`,
+///   `time`, `complete_ts`), an array of reminders for `list`, or `{ok, id}` for `delete`
+#[modular_agent(
+    title = "Reminder",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+    string_config(name = CONFIG_ACTION, default = "add"),
+    string_config(name = CONFIG_TIME),
+)]
+struct SlackReminderAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackReminderAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let action = value
+            .get_str(CONFIG_ACTION)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_ACTION));
+
+        let token = get_user_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let result = match action.as_str() {
+            "add" => {
+                let text = value
+                    .as_str()
+                    .or_else(|| value.get_str("text"))
+                    .filter(|t| !t.is_empty())
+                    .ok_or_else(|| {
+                        AgentError::InvalidValue("Reminder text is required".to_string())
+                    })?;
+                let time = match value.get(CONFIG_TIME) {
+                    Some(AgentValue::String(time)) => time.to_string(),
+                    Some(time) => time.to_integer().map(|t| t.to_string()).unwrap_or_default(),
+                    None => config.get_string_or_default(CONFIG_TIME),
+                };
+                if time.trim().is_empty() {
+                    return Err(AgentError::InvalidValue(
+                        "Reminder time is required".to_string(),
+                    ));
+                }
+                let user = match value.get_str(CONFIG_USER).filter(|u| !u.is_empty()) {
+                    Some(user) => Some(slack::resolve_user_id(&session, user).await?),
+                    None => None,
+                };
+                let reminder = slack::add_reminder(&session, text, &time, user.as_ref()).await?;
+                AgentValue::from_serialize(&reminder)?
+            }
+            "list" => AgentValue::from_serialize(&slack::list_reminders(&session).await?)?,
+            "delete" => {
+                let id = value
+                    .as_str()
+                    .or_else(|| value.get_str("id"))
+                    .filter(|id| !id.is_empty())
+                    .ok_or_else(|| {
+                        AgentError::InvalidValue("Reminder id is required".to_string())
+                    })?;
+                slack::delete_reminder(&session, id).await?;
+                AgentValue::object(hashmap! {
+                    "ok".into() => AgentValue::boolean(true),
+                    "id".into() => AgentValue::string(id),
+                })
+            }
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown reminder action: {} (expected add, list or delete)",
+                    other
+                )));
+            }
+        };
+
+        self.output(ctx, PORT_VALUE, result).await
+    }
+}
+
+/// Agent for managing channel bookmarks via `bookmarks.list` / `bookmarks.add` /
+/// `bookmarks.edit` / `bookmarks.remove`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `action`: Default action, used when the input has no `action`: `set` (default;
+///   update the bookmark with the same title, or add one), `add`, `list` or `remove`
+/// - `title`: Default bookmark title, used when the input has no `title`
+///
+/// # Input
+/// - `value`: Link URL, or an object with `link`, `title`, `emoji`, `channel`,
+///   `action`, and `id` for `remove` (or `title`, to remove bookmarks by title)
+///
+/// # Output
+/// - `value`: The bookmark (`id`, `channel_id`, `title`, `link`, `emoji`, `type`,
+///   `date_created`), an array of bookmarks for `list`, or `{ok, channel, removed}`
+///   for `remove`
+#[modular_agent(
+    title = "Bookmark",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_ACTION, default = "set"),
+    string_config(name = CONFIG_TITLE),
+)]
+struct SlackBookmarkAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackBookmarkAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = value
+            .get_str(CONFIG_CHANNEL)
+            .map(String::from)
+            .filter(|c| !c.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_CHANNEL))
+            .ok_or_else(|| AgentError::InvalidValue("Channel not configured".to_string()))?;
+        let action = value
+            .get_str(CONFIG_ACTION)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_ACTION));
+        let title = value
+            .get_str(CONFIG_TITLE)
+            .map(String::from)
+            .filter(|t| !t.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_TITLE));
+        let link = value
+            .as_str()
+            .or_else(|| value.get_str("link"))
+            .filter(|l| !l.is_empty());
+        let emoji = value.get_str("emoji").filter(|e| !e.is_empty());
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        let result = match action.as_str() {
+            "list" => {
+                AgentValue::from_serialize(&slack::list_bookmarks(&session, &channel_id).await?)?
+            }
+            "add" | "set" => {
+                let link = link.ok_or_else(|| {
+                    AgentError::InvalidValue("Bookmark link is required".to_string())
+                })?;
+                let title = title.as_deref().unwrap_or(link);
+                let existing = if action == "set" {
+                    slack::list_bookmarks(&session, &channel_id)
+                        .await?
+                        .into_iter()
+                        .find(|b| b.title == title)
+                } else {
+                    None
+                };
+                let bookmark = match existing {
+                    Some(existing) => {
+                        slack::edit_bookmark(
+                            &session,
+                            &channel_id,
+                            &existing.id,
+                            title,
+                            link,
+                            emoji,
+                        )
+                        .await?
+                    }
+                    None => slack::add_bookmark(&session, &channel_id, title, link, emoji).await?,
+                };
+                AgentValue::from_serialize(&bookmark)?
+            }
+            "remove" => {
+                let ids: Vec<String> = match value.get_str("id").filter(|id| !id.is_empty()) {
+                    Some(id) => vec![id.to_string()],
+                    None => {
+                        let title = title.ok_or_else(|| {
+                            AgentError::InvalidValue("Bookmark id or title is required".to_string())
+                        })?;
+                        slack::list_bookmarks(&session, &channel_id)
+                            .await?
+                            .into_iter()
+                            .filter(|b| b.title == title)
+                            .map(|b| b.id)
+                            .collect()
+                    }
+                };
+                for id in &ids {
+                    slack::remove_bookmark(&session, &channel_id, id).await?;
+                }
+                AgentValue::object(hashmap! {
+                    "ok".into() => AgentValue::boolean(true),
+                    "channel".into() => AgentValue::string(channel_id.to_string()),
+                    "removed".into() => AgentValue::array(ids.into_iter().map(AgentValue::string).collect()),
+                })
+            }
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown bookmark action: {} (expected set, add, list or remove)",
+                    other
+                )));
+            }
+        };
+
+        self.output(ctx, PORT_VALUE, result).await
+    }
+}
+
+/// Agent for saving messages or files for later via `stars.add` / `stars.remove`.
+///
+/// Saved items belong to a user, so this agent uses the user token
+/// (`slack_user_token` global config or `SLACK_USER_TOKEN`) instead of the bot token.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `action`: Default action, `add` or `remove`, used when the input has no `action`
+///
+/// # Input
+/// - `message`: Object with `channel` and `ts` for a message, or `file` (file ID) for a file,
+///   and optionally `action`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `action` and `item` (`channel` and `ts`, or `file`)
+#[modular_agent(
+    title = "Star",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_ACTION, default = "add"),
+    custom_global_config(name = CONFIG_SLACK_USER_TOKEN, type_ = "password", default = AgentValue::string(""), title = "Slack User Token"),
+)]
+struct SlackStarAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackStarAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+
+        let config = self.configs()?;
+        let action = value
+            .get_str(CONFIG_ACTION)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_ACTION));
+
+        let token = get_user_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let (item, item_value) = if let Some(file) = value.get_str("file").filter(|f| !f.is_empty())
+        {
+            (
+                slack::StarItem::File(file.into()),
+                AgentValue::object(hashmap! {
+                    "file".into() => AgentValue::string(file),
+                }),
+            )
+        } else {
+            let channel = content
+                .channel
+                .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+            if channel.is_empty() {
+                return Err(AgentError::InvalidValue(
+                    "Channel not configured".to_string(),
+                ));
+            }
+            let Some(ts) = content.ts.filter(|ts| !ts.is_empty()) else {
+                return Err(AgentError::InvalidValue(
+                    "Message ts or file is required".to_string(),
+                ));
+            };
+            (
+                slack::StarItem::Message {
+                    channel_id: slack::resolve_channel_id(&session, &channel).await?,
+                    ts: ts.clone().into(),
+                },
+                AgentValue::object(hashmap! {
+                    "channel".into() => AgentValue::string(channel),
+                    "ts".into() => AgentValue::string(ts),
+                }),
+            )
+        };
+
+        match action.as_str() {
+            "add" => slack::add_star(&session, &item).await?,
+            "remove" => slack::remove_star(&session, &item).await?,
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown star action: {} (expected add or remove)",
+                    other
+                )));
+            }
+        }
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "action".into() => AgentValue::string(action),
+            "item".into() => item_value,
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for attaching custom link previews via `chat.unfurl`.
+///
+/// Combined with a `link_shared` event this lets a flow act as an unfurl bot.
+///
+/// # Input
+/// - `message`: Object with `unfurls` (URL → array of blocks, or an object with
+///   `blocks`) and either `unfurl_id` and `source`, or `channel` and `ts` (or `message_ts`)
+///
+/// # Output
+/// - `result`: Object containing `ok` and `urls` (the unfurled URLs) on success
+#[modular_agent(
+    title = "Unfurl",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+)]
+struct SlackUnfurlAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackUnfurlAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let non_empty = |key: &str| value.get_str(key).filter(|v| !v.is_empty());
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let id = if let Some(unfurl_id) = non_empty("unfurl_id") {
+            let Some(source) = non_empty("source") else {
+                return Err(AgentError::InvalidValue(
+                    "source is required with unfurl_id".to_string(),
+                ));
+            };
+            SlackUnfurlRequestId::UnfurlIdSource(SlackUnfurlIdSource::new(
+                source.to_string(),
+                SlackUnfurlId(unfurl_id.to_string()),
+            ))
+        } else {
+            let Some(channel) = non_empty("channel") else {
+                return Err(AgentError::InvalidValue(
+                    "Channel not configured".to_string(),
+                ));
+            };
+            let Some(ts) = non_empty("ts").or_else(|| non_empty("message_ts")) else {
+                return Err(AgentError::InvalidValue(
+                    "Message ts is required".to_string(),
+                ));
+            };
+            let channel_id = slack::resolve_channel_id(&session, channel).await?;
+            SlackUnfurlRequestId::ChannelTs(SlackChannelTs::new(channel_id, ts.into()))
+        };
+
+        let Some(entries) = value.get_object("unfurls").filter(|u| !u.is_empty()) else {
+            return Err(AgentError::InvalidValue("unfurls is required".to_string()));
+        };
+        let mut unfurls = HashMap::new();
+        for (url, preview) in entries.iter() {
+            let blocks = match preview {
+                AgentValue::Object(_) => preview.get("blocks"),
+                _ => Some(preview),
+            };
+            let Some(blocks) = value_to_blocks(blocks, &Default::default())? else {
+                return Err(AgentError::InvalidValue(format!(
+                    "Invalid unfurl blocks for {}",
+                    url
+                )));
+            };
+            unfurls.insert(url.clone(), blocks);
+        }
+        let mut urls: Vec<String> = unfurls.keys().cloned().collect();
+        urls.sort();
+
+        slack::unfurl(&session, id, unfurls).await?;
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "urls".into() => AgentValue::array(urls.into_iter().map(AgentValue::string).collect()),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for publishing Markdown to a canvas via `canvases.create` / `canvases.edit`.
+///
+/// Without a canvas ID the agent creates one (the channel's canvas when a channel is
+/// set) and remembers it, so later inputs edit the same document.
+///
+/// # Configuration
+/// - `channel`: Channel whose canvas is created, used when the input has no `channel`.
+///   Empty creates a standalone canvas.
+/// - `canvas_id`: Canvas to edit instead of creating one
+/// - `title`: Title of a newly created canvas
+/// - `operation`: How text is applied to an existing canvas: `replace` (default),
+///   `append` or `prepend`
+///
+/// # Input
+/// - `message`: Markdown string, or object with `text` and optionally `title`, `channel`,
+///   `canvas_id`, `operation`. A `title` renames an existing canvas.
+///
+/// # Output
+/// - `result`: Object containing `ok`, `canvas_id`, `created` (and `channel`) on success
+#[modular_agent(
+    title = "Canvas",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_CANVAS_ID),
+    string_config(name = CONFIG_TITLE),
+    string_config(name = CONFIG_OPERATION, default = "replace"),
+)]
+struct SlackCanvasAgent {
+    data: AgentData,
+    /// Canvases created by this agent, keyed by channel (empty for standalone).
+    created: HashMap<String, String>,
+}
+
+#[async_trait]
+impl AsAgent for SlackCanvasAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+            created: HashMap::new(),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let content = extract_message_content(&value)?;
+        if content.text.trim().is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Canvas text is required".to_string(),
+            ));
+        }
+        let input = |key: &str| {
+            value
+                .get_str(key)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+        };
+
+        let config = self.configs()?;
+        let channel = content
+            .channel
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_CHANNEL));
+        let canvas_id = input(CONFIG_CANVAS_ID)
+            .or_else(|| non_empty_config(config, CONFIG_CANVAS_ID))
+            .or_else(|| self.created.get(&channel).cloned());
+        let title = input(CONFIG_TITLE);
+        let operation = input(CONFIG_OPERATION)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_OPERATION));
+        let operation = match operation.as_str() {
+            "" | "replace" => SlackCanvasOperation::Replace,
+            "append" => SlackCanvasOperation::InsertAtEnd,
+            "prepend" => SlackCanvasOperation::InsertAtStart,
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown canvas operation: {} (expected replace, append or prepend)",
+                    other
+                )));
+            }
+        };
+        let create_title = title
+            .clone()
+            .or_else(|| non_empty_config(config, CONFIG_TITLE));
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let (canvas_id, created) = match canvas_id {
+            Some(canvas_id) => {
+                let change = SlackCanvasChange::new(operation)
+                    .with_document_content(SlackCanvasDocumentContent::new(content.text));
+                slack::edit_canvas(&session, &canvas_id, change).await?;
+                if let Some(title) = title {
+                    let rename = SlackCanvasChange::new(SlackCanvasOperation::Rename)
+                        .with_title_content(SlackCanvasDocumentContent::new(title));
+                    slack::edit_canvas(&session, &canvas_id, rename).await?;
+                }
+                (canvas_id, false)
+            }
+            None => {
+                let channel_id = if channel.is_empty() {
+                    None
+                } else {
+                    Some(slack::resolve_channel_id(&session, &channel).await?)
+                };
+                let canvas_id =
+                    slack::create_canvas(&session, create_title, content.text, channel_id.as_ref())
+                        .await?;
+                self.created.insert(channel.clone(), canvas_id.clone());
+                (canvas_id, true)
+            }
+        };
+
+        let mut result = hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "canvas_id".into() => AgentValue::string(canvas_id),
+            "created".into() => AgentValue::boolean(created),
+        };
+        if !channel.is_empty() {
+            result.insert("channel".into(), AgentValue::string(channel));
+        }
+
+        self.output(ctx, PORT_RESULT, AgentValue::object(result))
+            .await
+    }
+}
+
+/// Fields extracted from a `message` input value.
+#[derive(Default)]
+struct MessageContent {
+    text: String,
+    blocks: Option<AgentValue>,
+    thread_ts: Option<String>,
+    channel: Option<String>,
+    ts: Option<String>,
+    user: Option<String>,
+    reply_broadcast: Option<bool>,
+    also_send_to_channel: Option<bool>,
+    username: Option<String>,
+    icon_emoji: Option<String>,
+    icon_url: Option<String>,
+    metadata: Option<AgentValue>,
+}
+
+fn extract_message_content(value: &AgentValue) -> Result<MessageContent, AgentError> {
+    match value {
+        AgentValue::String(s) => Ok(MessageContent {
+            text: s.to_string(),
+            ..Default::default()
+        }),
+        AgentValue::Message(msg) => Ok(MessageContent {
+            text: msg.content.clone(),
+            ..Default::default()
+        }),
+        AgentValue::Object(obj) => {
+            let text = obj
+                .get("text")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let blocks = obj.get("blocks").cloned();
+            let thread_ts = obj
+                .get("thread_ts")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            let ts = obj.get("ts").and_then(|v| v.as_str()).map(String::from);
+            let non_empty = |key: &str| {
+                obj.get(key)
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from)
+            };
+            Ok(MessageContent {
+                text,
+                blocks,
+                thread_ts,
+                channel: non_empty("channel"),
+                ts,
+                user: non_empty("user"),
+                reply_broadcast: obj.get("reply_broadcast").and_then(|v| v.as_bool()),
+                also_send_to_channel: obj.get("also_send_to_channel").and_then(|v| v.as_bool()),
+                username: non_empty("username"),
+                icon_emoji: non_empty("icon_emoji"),
+                icon_url: non_empty("icon_url"),
+                metadata: obj.get("metadata").cloned(),
+            })
+        }
+        AgentValue::Array(arr) => {
+            let texts: Vec<String> = arr
+                .iter()
+                .filter_map(|v| {
+                    v.as_str()
+                        .map(String::from)
+                        .or_else(|| v.as_message().map(|m| m.content.clone()))
+                })
+                .collect();
+            Ok(MessageContent {
+                text: texts.join("\n"),
+                ..Default::default()
+            })
+        }
+        _ => {
+            let json = serde_json::to_string_pretty(&value.to_json()).unwrap_or_default();
+            Ok(MessageContent {
+                text: format!("```\n{}\n```", json),
+                ..Default::default()
+            })
+        }
+    }
+}
+
+/// Converts a JSON-like blocks value into Slack blocks.
+///
+/// The blocks are checked with [`blocks::validate_blocks`] first; anything else
+/// slack-morphism cannot represent is ignored.
+fn value_to_blocks(
+    value: Option<&AgentValue>,
+    options: &mrkdwn::MrkdwnOptions,
+) -> Result<Option<Vec<SlackBlock>>, AgentError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let mut json = value.to_json();
+    blocks::validate_blocks(&json)?;
+    if options.special_mentions == mrkdwn::SpecialMentions::Escape {
+        blocks::escape_special_mentions(&mut json);
+    }
+    Ok(serde_json::from_value::<Vec<SlackBlock>>(json).ok())
+}
+
+/// Converts a `{event_type, event_payload}` object into Slack message metadata.
+///
+/// Non-string payload values are serialized as JSON strings.
+fn value_to_metadata(value: &AgentValue) -> Result<SlackMessageMetadata, AgentError> {
+    let Some(event_type) = value.get_str("event_type").filter(|t| !t.is_empty()) else {
+        return Err(AgentError::InvalidValue(
+            "metadata.event_type is required".to_string(),
+        ));
+    };
+
+    let mut metadata = SlackMessageMetadata::new(event_type.to_string());
+    if let Some(payload) = value.get_object("event_payload") {
+        let payload = payload
+            .iter()
+            .map(|(k, v)| {
+                let v = v
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| v.to_json().to_string());
+                (k.clone(), v)
+            })
+            .collect();
+        metadata = metadata.with_event_payload(payload);
+    }
+    Ok(metadata)
+}
+
+fn metadata_to_value(metadata: &SlackMessageMetadata) -> AgentValue {
+    let mut obj = im::HashMap::new();
+    obj.insert(
+        "event_type".into(),
+        AgentValue::string(metadata.event_type.clone()),
+    );
+    if let Some(payload) = &metadata.event_payload {
+        let payload = payload
+            .iter()
+            .map(|(k, v)| (k.clone(), AgentValue::string(v.clone())))
+            .collect();
+        obj.insert("event_payload".into(), AgentValue::object(payload));
+    }
+    AgentValue::object(obj)
+}
+
+/// Reads a channel from a string or an object with an `id` (e.g. a Channels entry).
+fn channel_from_value(value: &AgentValue) -> Option<String> {
+    value
+        .as_str()
+        .or_else(|| value.get_str("id"))
+        .or_else(|| value.get_str("channel"))
+        .filter(|c| !c.is_empty())
+        .map(String::from)
+}
+
+/// Agent for building Block Kit blocks from a declarative spec.
+///
+/// The output can be passed as `blocks` to the Post agent.
+///
+/// # Configuration
+/// - `convert_markdown`: Convert Markdown/HTML in section, field and context texts
+///   to Slack mrkdwn (default: true)
+/// - `date_format`, `convert_special_mentions`, `escape_special_mentions`: Same as Post
+///
+/// # Input
+/// - `value`: Object with `header`, `section`/`sections`, `fields`, `image`/`images`,
+///   `buttons`, `context`, `divider` keys, or an array of such objects
+///
+/// # Output
+/// - `blocks`: Array of Block Kit blocks
+#[modular_agent(
+    title = "Blocks",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_BLOCKS],
+    boolean_config(name = CONFIG_CONVERT_MARKDOWN, default = true),
+    string_config(name = CONFIG_DATE_FORMAT),
+    boolean_config(name = CONFIG_CONVERT_SPECIAL_MENTIONS),
+    boolean_config(name = CONFIG_ESCAPE_SPECIAL_MENTIONS),
+)]
+struct SlackBlocksAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackBlocksAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let options = mrkdwn_options(config);
+        let convert = config.get_bool_or(CONFIG_CONVERT_MARKDOWN, true);
+
+        let blocks = blocks::build_blocks(&value.to_json(), convert.then_some(&options))?;
+        let mut json = serde_json::to_value(&blocks)
+            .map_err(|e| AgentError::InvalidValue(format!("Invalid blocks: {}", e)))?;
+        if options.special_mentions == mrkdwn::SpecialMentions::Escape {
+            blocks::escape_special_mentions(&mut json);
+        }
+        let blocks = AgentValue::from_serialize(&json)?;
+        blocks::validate_blocks(&blocks.to_json())?;
+        self.output(ctx, PORT_BLOCKS, blocks).await
+    }
+}
+
+/// Agent for fetching message history from a Slack channel.
+///
+/// # Configuration
+/// - `channel`: The Slack channel name or ID to fetch history from
+/// - `limit`: Maximum number of messages to fetch (default: 10). With `fetch_all`,
+///   the number of messages per page (default: 200).
+/// - `fetch_all`: Follow pagination cursors until the history is exhausted (default: false)
+/// - `max_messages`: With `fetch_all`, stop after this many messages (default: 0, unlimited)
+/// - `oldest`, `latest`: Only fetch messages in this time range. Each is a Slack ts,
+///   an RFC 3339 timestamp, or a relative time such as `-24h` or `-7d`.
+/// - `include_reactions`: Add each message's `reactions` (`name`, `count`, `users`)
+///   (default: false)
+/// - `resolve_users`: Add the sender's `user_name` and rewrite `<@U...>` mentions in the
+///   text to `@name`, looking users up with `users.info` (default: false). Names are
+///   cached for the lifetime of the agent.
+/// - `user`: Only return messages from these users (comma-separated user IDs)
+/// - `exclude_subtypes`: Comma-separated message subtypes to drop (default:
+///   `channel_join,channel_leave`)
+/// - `emit_individually`: Output each message on its own as pages are fetched instead
+///   of one array, followed by a `{done: true, channel, count}` marker (default: false)
+/// - `include_permalinks`: Add each message's `permalink` (default: false)
+/// - `incremental`: Only output messages newer than those seen by earlier runs, for
+///   polling a channel without Socket Mode (default: false). Implies `fetch_all` and
+///   ignores `max_messages` so no message is skipped. The
+///   newest ts seen is stored in `cursor`, which is saved with the preset; clear it to
+///   start over from `oldest`.
+///
+/// # Input
+/// - `trigger`: Any value triggers fetching the history. An object may carry `oldest`,
+///   `latest` and `user` (an ID or an array of IDs), which override the configuration.
+///
+/// # Output
+/// - `values`: Array of Slack message objects containing `text`, `user`, `ts`, etc.
+///   Messages with a subtype (e.g. `bot_message`) carry `subtype`, and messages posted
+///   by apps carry `bot_id`.
+///   Messages with attachments carry a `files` array (`id`, `name`, `title`, `mimetype`,
+///   `filetype`, `url_private`, `permalink`, `user`, `created`, `size`).
+#[modular_agent(
+    title = "History",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_VALUES],
+    string_config(name = CONFIG_CHANNEL),
+    integer_config(name = CONFIG_LIMIT),
+    boolean_config(name = CONFIG_FETCH_ALL),
+    integer_config(name = CONFIG_MAX_MESSAGES),
+    string_config(name = CONFIG_OLDEST),
+    string_config(name = CONFIG_LATEST),
+    boolean_config(name = CONFIG_INCLUDE_REACTIONS),
+    boolean_config(name = CONFIG_RESOLVE_USERS),
+    string_config(name = CONFIG_USER),
+    string_config(name = CONFIG_EXCLUDE_SUBTYPES, default = "channel_join,channel_leave"),
+    boolean_config(name = CONFIG_EMIT_INDIVIDUALLY),
+    boolean_config(name = CONFIG_INCLUDE_PERMALINKS),
+    boolean_config(name = CONFIG_INCREMENTAL),
+    string_config(name = CONFIG_CURSOR, detail),
+)]
+struct SlackHistoryAgent {
+    data: AgentData,
+    /// User ID to name, filled when `resolve_users` is enabled.
+    user_names: HashMap<String, String>,
+    /// Workspace URL for building permalinks, fetched when `include_permalinks` is enabled.
+    workspace_url: Option<String>,
+}
+
+#[async_trait]
+impl AsAgent for SlackHistoryAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+            user_names: HashMap::new(),
+            workspace_url: None,
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = config.get_string(CONFIG_CHANNEL)?;
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let now = chrono::Utc::now();
+        let time_bound = |key: &str| {
+            value
+                .get_str(key)
+                .map(String::from)
+                .or_else(|| non_empty_config(config, key))
+                .filter(|t| !t.trim().is_empty())
+                .map(|t| slack::parse_history_time(&t, now))
+                .transpose()
+        };
+        let incremental = config.get_bool_or_default(CONFIG_INCREMENTAL);
+        let cursor = non_empty_config(config, CONFIG_CURSOR).filter(|_| incremental);
+        let oldest = match (value.get_str(CONFIG_OLDEST), cursor) {
+            (None, Some(cursor)) => Some(slack::parse_history_time(&cursor, now)?),
+            _ => time_bound(CONFIG_OLDEST)?,
+        };
+        let latest = time_bound(CONFIG_LATEST)?;
+        let users = history_users(&value, config);
+
+        let token = get_token(self.ma())?;
+        let fetch_all = incremental || config.get_bool_or_default(CONFIG_FETCH_ALL);
+        let limit = config.get_integer_or_default(CONFIG_LIMIT);
+        let limit = match limit {
+            l if l > 0 => l.min(999) as u16,
+            _ if fetch_all => 200,
+            _ => 10,
+        };
+        let max_messages = config.get_integer_or_default(CONFIG_MAX_MESSAGES);
+        let options = slack::HistoryOptions {
+            limit,
+            fetch_all,
+            max_messages: (fetch_all && !incremental && max_messages > 0)
+                .then_some(max_messages as usize),
+            max_retries: 3,
+            oldest,
+            latest,
+        };
+
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let emit_individually = config.get_bool_or_default(CONFIG_EMIT_INDIVIDUALLY);
+        let filter = HistoryFilter {
+            exclude_subtypes: config
+                .get_string_or_default(CONFIG_EXCLUDE_SUBTYPES)
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+            users,
+            include_reactions: config.get_bool_or_default(CONFIG_INCLUDE_REACTIONS),
+            resolve_users: config.get_bool_or_default(CONFIG_RESOLVE_USERS),
+        };
+
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let permalink_base = if config.get_bool_or_default(CONFIG_INCLUDE_PERMALINKS) {
+            if self.workspace_url.is_none() {
+                self.workspace_url = Some(slack::workspace_url(&session).await?);
+            }
+            self.workspace_url.clone()
+        } else {
+            None
+        };
+        let mut pager = slack::HistoryPager::new(channel_id.clone(), options);
+        let mut messages = Vector::new();
+        let mut count = 0;
+        let mut newest_ts: Option<String> = None;
+        while let Some(page) = pager.next_page(&session).await? {
+            // Track the newest ts before filtering so dropped messages are not refetched.
+            newest_ts = page
+                .iter()
+                .map(|msg| msg.message.origin.ts.to_string())
+                .chain(newest_ts.take())
+                .max_by_key(|ts| ts_sort_key(ts));
+            for message in self
+                .history_values(
+                    &session,
+                    page,
+                    &filter,
+                    &channel_id,
+                    permalink_base.as_deref(),
+                )
+                .await?
+            {
+                count += 1;
+                if emit_individually {
+                    self.output(ctx.clone(), PORT_VALUES, message).await?;
+                } else {
+                    messages.push_back(message);
+                }
+            }
+        }
+
+        if let Some(ts) = newest_ts.filter(|_| incremental) {
+            self.set_config(CONFIG_CURSOR.into(), AgentValue::string(ts))?;
+        }
+
+        if emit_individually {
+            let done = hashmap! {
+                "done".into() => AgentValue::boolean(true),
+                "channel".into() => AgentValue::string(channel_id.to_string()),
+                "count".into() => AgentValue::integer(count),
+            };
+            return self
+                .output(ctx, PORT_VALUES, AgentValue::object(done))
+                .await;
+        }
+        self.output(ctx, PORT_VALUES, AgentValue::array(messages))
+            .await
+    }
+}
+
+/// Per-message filtering and enrichment settings of the History agent.
+struct HistoryFilter {
+    exclude_subtypes: Vec<String>,
+    users: Vec<String>,
+    include_reactions: bool,
+    resolve_users: bool,
+}
+
+impl SlackHistoryAgent {
+    /// Filters one page of history and converts it to agent values.
+    async fn history_values(
+        &mut self,
+        session: &slack::Session<'_>,
+        mut history: Vec<slack::HistoryMessage>,
+        filter: &HistoryFilter,
+        channel_id: &SlackChannelId,
+        permalink_base: Option<&str>,
+    ) -> Result<Vec<AgentValue>, AgentError> {
+        if !filter.exclude_subtypes.is_empty() {
+            history.retain(|msg| {
+                msg.message
+                    .subtype
+                    .as_ref()
+                    .is_none_or(|t| !filter.exclude_subtypes.contains(&subtype_name(t)))
+            });
+        }
+        if !filter.users.is_empty() {
+            history.retain(|msg| {
+                msg.message
+                    .sender
+                    .user
+                    .as_ref()
+                    .is_some_and(|u| filter.users.iter().any(|id| id == u.as_ref()))
+            });
+        }
+
+        if filter.resolve_users {
+            let user_ids = history.iter().flat_map(|msg| {
+                let text = msg.message.content.text.as_deref().unwrap_or_default();
+                msg.message
+                    .sender
+                    .user
+                    .iter()
+                    .map(|u| u.to_string())
+                    .chain(mrkdwn::mentioned_user_ids(text))
+            });
+            slack::resolve_user_names(session, user_ids.collect::<Vec<_>>(), &mut self.user_names)
+                .await;
+        }
+
+        let mut messages = Vec::with_capacity(history.len());
+        for msg in &history {
+            let mut message = history_message_to_value(msg);
+            if filter.resolve_users {
+                if let Some(name) = msg
+                    .message
+                    .sender
+                    .user
+                    .as_ref()
+                    .and_then(|u| self.user_names.get(u.as_ref()))
+                {
+                    message.set("user_name".into(), AgentValue::string(name.clone()))?;
+                }
+                if let Some(text) = &msg.message.content.text {
+                    let text = mrkdwn::replace_user_mentions(text, &self.user_names);
+                    message.set("text".into(), AgentValue::string(text))?;
+                }
+            }
+            if filter.include_reactions {
+                let reactions = msg.message.content.reactions.as_deref().unwrap_or_default();
+                message.set(
+                    "reactions".into(),
+                    AgentValue::array(reactions.iter().map(reaction_to_value).collect()),
+                )?;
+            }
+            if let Some(base) = permalink_base {
+                let permalink = slack::message_permalink(
+                    base,
+                    channel_id.as_ref(),
+                    msg.message.origin.ts.as_ref(),
+                    msg.message.origin.thread_ts.as_ref().map(|t| t.as_ref()),
+                );
+                message.set("permalink".into(), AgentValue::string(permalink))?;
+            }
+            messages.push(message);
+        }
+        Ok(messages)
+    }
+}
+
+/// Reads the History user filter from the input's `user` (an ID or an array of IDs)
+/// or the comma-separated `user` config.
+fn history_users(value: &AgentValue, config: &AgentConfigs) -> Vec<String> {
+    let users: Vec<String> = match value.get(CONFIG_USER) {
+        Some(AgentValue::Array(arr)) => arr
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect(),
+        Some(AgentValue::String(user)) => vec![user.to_string()],
+        _ => config
+            .get_string_or_default(CONFIG_USER)
+            .split(',')
+            .map(String::from)
+            .collect(),
+    };
+    users
+        .into_iter()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .collect()
+}
+
+fn slack_message_to_agent_value(msg: &SlackHistoryMessage) -> AgentValue {
+    let mut obj = im::HashMap::new();
+
+    // SlackHistoryMessage uses #[serde(flatten)] so fields are directly accessible
+    if let Some(text) = &msg.content.text {
+        obj.insert("text".into(), AgentValue::string(text.clone()));
+    }
+
+    if let Some(user) = &msg.sender.user {
+        obj.insert("user".into(), AgentValue::string(user.to_string()));
+    }
+
+    if let Some(bot_id) = &msg.sender.bot_id {
+        obj.insert("bot_id".into(), AgentValue::string(bot_id.to_string()));
+    }
+
+    obj.insert("ts".into(), AgentValue::string(msg.origin.ts.to_string()));
+
+    if let Some(thread_ts) = &msg.origin.thread_ts {
+        obj.insert(
+            "thread_ts".into(),
+            AgentValue::string(thread_ts.to_string()),
+        );
+    }
+
+    if let Some(subtype) = &msg.subtype {
+        obj.insert("subtype".into(), AgentValue::string(subtype_name(subtype)));
+    }
+
+    if let Some(files) = msg.content.files.as_ref().filter(|f| !f.is_empty()) {
+        obj.insert(
+            "files".into(),
+            AgentValue::array(files.iter().map(|f| file_to_value(f, None)).collect()),
+        );
+    }
+
+    AgentValue::object(obj)
+}
+
+/// Like [`slack_message_to_agent_value`], with the sizes of the attached files.
+fn history_message_to_value(msg: &slack::HistoryMessage) -> AgentValue {
+    let mut value = slack_message_to_agent_value(&msg.message);
+    if !msg.files.is_empty()
+        && let Some(obj) = value.as_object_mut()
+    {
+        let files = msg.files.iter().map(|f| file_to_value(&f.file, f.size));
+        obj.insert("files".into(), AgentValue::array(files.collect()));
+    }
+    value
+}
+
+/// Returns Slack's name for a message subtype, e.g. `channel_join`.
+fn subtype_name(subtype: &SlackMessageEventType) -> String {
+    serde_json::to_value(subtype)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+/// Describes a file as `{id, name, title, mimetype, filetype, url_private, permalink}`,
+/// omitting fields Slack did not return, plus `size` when it is known.
+fn file_to_value(file: &SlackFile, size: Option<u64>) -> AgentValue {
+    let mut obj = im::HashMap::new();
+    obj.insert("id".into(), AgentValue::string(file.id.to_string()));
+    let fields = [
+        ("name", file.name.clone()),
+        ("title", file.title.clone()),
+        ("mimetype", file.mimetype.as_ref().map(|m| m.to_string())),
+        ("filetype", file.filetype.as_ref().map(|t| t.to_string())),
+        (
+            "url_private",
+            file.url_private.as_ref().map(|u| u.to_string()),
+        ),
+        ("permalink", file.permalink.as_ref().map(|u| u.to_string())),
+        ("user", file.user.as_ref().map(|u| u.to_string())),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            obj.insert(key.into(), AgentValue::string(value));
+        }
+    }
+    if let Some(created) = &file.created {
+        obj.insert("created".into(), AgentValue::integer(created.0.as_second()));
+    }
+    if let Some(size) = size {
+        obj.insert("size".into(), AgentValue::integer(size as i64));
+    }
+    AgentValue::object(obj)
+}
+
+/// Agent for exporting a channel's full history as JSONL, CSV or JSON.
+///
+/// Follows pagination to the start of the channel, optionally expands threads,
+/// and resolves user IDs to names. Records are written oldest first, with thread
+/// replies following their parent.
+///
+/// # Configuration
+/// - `channel`: The Slack channel name or ID to export
+/// - `format`: `jsonl` (default, also accepted as `ndjson`), `csv` or `json`
+/// - `path`: Optional file path. If set, the export is streamed there page by page
+///   instead of being output.
+/// - `include_threads`: Include thread replies (default: true)
+///
+/// # Input
+/// - `trigger`: Any value triggers the export
+///
+/// # Output
+/// - `value`: The exported text, or the file path when `path` is set. With `json`,
+///   a `{channel, count, messages}` object instead of text. JSON records carry the
+///   metadata of attached `files`.
+#[modular_agent(
+    title = "ExportHistory",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_VALUE],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_FORMAT, default = "jsonl"),
+    string_config(name = CONFIG_PATH),
+    boolean_config(name = CONFIG_INCLUDE_THREADS, default = true),
+)]
+struct SlackExportHistoryAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackExportHistoryAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        _value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = config.get_string(CONFIG_CHANNEL)?;
+        if channel.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "Channel not configured".to_string(),
+            ));
+        }
+        let format = ExportFormat::parse(&config.get_string_or(CONFIG_FORMAT, "jsonl"))?;
+        let path = config.get_string_or_default(CONFIG_PATH);
+        let include_threads = config.get_bool_or(CONFIG_INCLUDE_THREADS, true);
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        if !path.is_empty() {
+            export_history_to_file(&session, &channel_id, format, include_threads, &path).await?;
+            return self.output(ctx, PORT_VALUE, AgentValue::string(path)).await;
+        }
+
+        let mut history =
+            slack::fetch_history_with_options(&session, &channel_id, &export_history_options())
+                .await?;
+        history.reverse();
+
+        let mut messages = Vec::with_capacity(history.len());
+        for msg in history {
+            messages
+                .extend(with_thread_replies(&session, &channel_id, msg, include_threads).await?);
+        }
+
+        let mut user_names = HashMap::new();
+        for msg in &messages {
+            export_user_name(&session, &mut user_names, msg).await;
+        }
+
+        if format == ExportFormat::Json {
+            let records: Vec<serde_json::Value> = messages
+                .iter()
+                .map(|msg| {
+                    let user = msg.message.sender.user.as_ref();
+                    export_json(msg, user.and_then(|u| user_names.get(u)).cloned())
+                })
+                .collect();
+            let export = serde_json::json!({
+                "channel": channel_id.to_string(),
+                "count": records.len(),
+                "messages": records,
+            });
+            return self
+                .output(ctx, PORT_VALUE, AgentValue::from_json(export)?)
+                .await;
+        }
+
+        let mut output = String::new();
+        if format == ExportFormat::Csv {
+            output.push_str("ts,thread_ts,user,user_name,text\n");
+        }
+        for msg in &messages {
+            let user = msg.message.sender.user.as_ref();
+            let user_name = user.and_then(|u| user_names.get(u)).cloned();
+            output.push_str(&export_record(format, msg, user_name));
+            output.push('\n');
+        }
+
+        self.output(ctx, PORT_VALUE, AgentValue::string(output))
+            .await
+    }
+}
+
+/// Pages through the whole history, retrying pages Slack rate-limits.
+fn export_history_options() -> slack::HistoryOptions {
+    slack::HistoryOptions {
+        limit: 200,
+        fetch_all: true,
+        max_retries: 3,
+        ..Default::default()
+    }
+}
+
+/// Returns `msg` followed by its thread replies when `include_threads` is set.
+async fn with_thread_replies(
+    session: &slack::Session<'_>,
+    channel_id: &SlackChannelId,
+    msg: slack::HistoryMessage,
+    include_threads: bool,
+) -> Result<Vec<slack::HistoryMessage>, AgentError> {
+    if !include_threads || msg.message.parent.reply_count.unwrap_or(0) == 0 {
+        return Ok(vec![msg]);
+    }
+    let ts = msg.message.origin.ts.clone();
+    let replies = slack::fetch_replies(session, channel_id, &ts).await?;
+    let mut messages = vec![msg];
+    // The first entry of conversations.replies is the parent itself
+    messages.extend(replies.into_iter().filter(|r| r.message.origin.ts != ts));
+    Ok(messages)
+}
+
+/// Looks up the name of the author of `msg`, caching it in `user_names`.
+///
+/// Falls back to the user ID when the lookup fails.
+async fn export_user_name(
+    session: &slack::Session<'_>,
+    user_names: &mut HashMap<SlackUserId, String>,
+    msg: &slack::HistoryMessage,
+) -> Option<String> {
+    let user = msg.message.sender.user.as_ref()?;
+    if !user_names.contains_key(user) {
+        let name = slack::fetch_user_name(session, user)
+            .await
+            .unwrap_or_else(|_| user.to_string());
+        user_names.insert(user.clone(), name);
+    }
+    user_names.get(user).cloned()
+}
+
+/// Writes a channel's export to `path` one history page at a time, so a long
+/// history never sits in memory as a whole.
+///
+/// Slack pages newest first, so each message and its thread are appended to a
+/// `<path>.part` scratch file as they arrive, and the scratch file is then copied
+/// to `path` in reverse, oldest first. The output matches the in-memory export.
+async fn export_history_to_file(
+    session: &slack::Session<'_>,
+    channel_id: &SlackChannelId,
+    format: ExportFormat,
+    include_threads: bool,
+    path: &str,
+) -> Result<(), AgentError> {
+    let io_err =
+        |e: std::io::Error| AgentError::IoError(format!("Failed to write {}: {}", path, e));
+    let scratch_path = format!("{}.part", path);
+
+    // Byte ranges of each message (with its replies) in the scratch file
+    let mut chunks: Vec<(u64, usize)> = Vec::new();
+    let mut offset = 0;
+    let mut count = 0;
+    let mut user_names = HashMap::new();
+    let mut scratch = BufWriter::new(
+        tokio::fs::File::create(&scratch_path)
+            .await
+            .map_err(io_err)?,
+    );
+    let mut pager = slack::HistoryPager::new(channel_id.clone(), export_history_options());
+    while let Some(page) = pager.next_page(session).await? {
+        for msg in page {
+            let mut chunk = String::new();
+            for msg in with_thread_replies(session, channel_id, msg, include_threads).await? {
+                let user_name = export_user_name(session, &mut user_names, &msg).await;
+                chunk.push_str(&export_record(format, &msg, user_name));
+                chunk.push('\n');
+                count += 1;
+            }
+            scratch.write_all(chunk.as_bytes()).await.map_err(io_err)?;
+            chunks.push((offset, chunk.len()));
+            offset += chunk.len() as u64;
+        }
+    }
+    scratch.flush().await.map_err(io_err)?;
+    drop(scratch);
+
+    let mut scratch = tokio::fs::File::open(&scratch_path).await.map_err(io_err)?;
+    let mut out = BufWriter::new(tokio::fs::File::create(path).await.map_err(io_err)?);
+    match format {
+        ExportFormat::Csv => out.write_all(b"ts,thread_ts,user,user_name,text\n").await,
+        ExportFormat::Json => {
+            let channel = serde_json::Value::String(channel_id.to_string());
+            let header = format!(
+                "{{\n  \"channel\": {},\n  \"count\": {},\n  \"messages\": [",
+                channel, count
+            );
+            out.write_all(header.as_bytes()).await
+        }
+        ExportFormat::Jsonl => Ok(()),
+    }
+    .map_err(io_err)?;
+
+    let mut first = true;
+    for (offset, len) in chunks.into_iter().rev() {
+        let mut buf = vec![0; len];
+        scratch
+            .seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(io_err)?;
+        scratch.read_exact(&mut buf).await.map_err(io_err)?;
+        if format != ExportFormat::Json {
+            out.write_all(&buf).await.map_err(io_err)?;
+            continue;
+        }
+        // Records are one compact JSON object per line; pretty-print them in place
+        for line in String::from_utf8_lossy(&buf).lines() {
+            let record: serde_json::Value = serde_json::from_str(line)
+                .map_err(|e| AgentError::IoError(format!("Invalid export record: {}", e)))?;
+            let record = format!("{:#}", record).replace('\n', "\n    ");
+            let separator = if first { "\n    " } else { ",\n    " };
+            first = false;
+            out.write_all(format!("{}{}", separator, record).as_bytes())
+                .await
+                .map_err(io_err)?;
+        }
+    }
+    if format == ExportFormat::Json {
+        let footer: &[u8] = if first { b"]\n}" } else { b"\n  ]\n}" };
+        out.write_all(footer).await.map_err(io_err)?;
+    }
+    out.flush().await.map_err(io_err)?;
+
+    tokio::fs::remove_file(&scratch_path).await.map_err(io_err)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Jsonl,
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    fn parse(s: &str) -> Result<Self, AgentError> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(AgentError::InvalidConfig(format!(
+                "Unknown export format: {}",
+                other
+            ))),
+        }
+    }
+}
+
+fn export_record(
+    format: ExportFormat,
+    msg: &slack::HistoryMessage,
+    user_name: Option<String>,
+) -> String {
+    if format != ExportFormat::Csv {
+        return export_json(msg, user_name).to_string();
+    }
+    let ts = msg.message.origin.ts.to_string();
+    let thread_ts = msg.message.origin.thread_ts.as_ref().map(|t| t.to_string());
+    let user = msg.message.sender.user.as_ref().map(|u| u.to_string());
+    let text = msg.message.content.text.clone().unwrap_or_default();
+    [
+        ts,
+        thread_ts.unwrap_or_default(),
+        user.unwrap_or_default(),
+        user_name.unwrap_or_default(),
+        text,
+    ]
+    .iter()
+    .map(|field| csv_field(field))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Describes a message as an export record, adding the metadata of attached files.
+fn export_json(msg: &slack::HistoryMessage, user_name: Option<String>) -> serde_json::Value {
+    let mut record = serde_json::json!({
+        "ts": msg.message.origin.ts.to_string(),
+        "thread_ts": msg.message.origin.thread_ts.as_ref().map(|t| t.to_string()),
+        "user": msg.message.sender.user.as_ref().map(|u| u.to_string()),
+        "user_name": user_name,
+        "text": msg.message.content.text.clone().unwrap_or_default(),
+    });
+    if !msg.files.is_empty() {
+        record["files"] = msg
+            .files
+            .iter()
+            .map(|f| file_to_value(&f.file, f.size).to_json())
+            .collect();
+    }
+    record
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Agent for listing Slack channels.
+///
+/// # Configuration
+/// - `limit`: Maximum number of channels to fetch (default: 100). With `fetch_all`,
+///   the number of channels per page (default: 1000).
+/// - `fetch_all`: Follow pagination cursors until every channel is listed, pausing
+///   between pages to stay within Slack's rate limit (default: false)
+/// - `max_channels`: With `fetch_all`, stop after this many channels (default: 0,
+///   unlimited)
+/// - `types`: Comma-separated conversation types to list: `public_channel`,
+///   `private_channel`, `im`, `mpim` (default: empty, public channels only)
+/// - `exclude_archived`: Leave archived channels out of the list (default: false)
+/// - `name_filter`: Only list channels whose name matches this glob (e.g. `proj-*`)
+///   or `/regex/`. `max_channels` counts matching channels only.
+/// - `enrich`: Add `created` (Unix time) and `last_message_ts` to each channel,
+///   peeking at its history with one `conversations.history` request. Requests are
+///   paced to stay within the rate limit and retried when rate-limited (default: false)
+///
+/// # Input
+/// - `trigger`: Any value triggers fetching the channel list
+///
+/// # Output
+/// - `channels`: Array of channel objects containing `id`, `name`, `is_private`, etc.
+#[modular_agent(
+    title = "Channels",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_CHANNELS],
+    integer_config(name = CONFIG_LIMIT),
+    boolean_config(name = CONFIG_FETCH_ALL),
+    integer_config(name = CONFIG_MAX_CHANNELS),
+    string_config(name = CONFIG_TYPES),
+    boolean_config(name = CONFIG_EXCLUDE_ARCHIVED),
+    string_config(name = CONFIG_NAME_FILTER),
+    boolean_config(name = CONFIG_ENRICH),
+)]
+struct SlackChannelsAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackChannelsAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        _value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let token = get_token(self.ma())?;
+        let fetch_all = config.get_bool_or_default(CONFIG_FETCH_ALL);
+        let limit = match config.get_integer_or_default(CONFIG_LIMIT) {
+            l if l > 0 => l.min(1000) as u16,
+            _ if fetch_all => 1000,
+            _ => 100,
+        };
+        let max_channels = config.get_integer_or_default(CONFIG_MAX_CHANNELS);
+        let options = slack::ChannelListOptions {
+            limit,
+            fetch_all,
+            max_channels: (fetch_all && max_channels > 0).then_some(max_channels as usize),
+            types: slack::parse_conversation_types(&config.get_string_or_default(CONFIG_TYPES))?,
+            exclude_archived: config.get_bool_or_default(CONFIG_EXCLUDE_ARCHIVED),
+            name_pattern: non_empty_config(config, CONFIG_NAME_FILTER)
+                .map(|p| slack::parse_name_pattern(&p))
+                .transpose()?,
+        };
+
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let enrich = config.get_bool_or_default(CONFIG_ENRICH);
+        let mut channels = Vector::new();
+        for (i, ch) in slack::list_channels(&session, &options)
+            .await?
+            .iter()
+            .enumerate()
+        {
+            let mut value = slack_channel_to_agent_value(ch);
+            if enrich {
+                if i > 0 {
+                    tokio::time::sleep(slack::HISTORY_PEEK_INTERVAL).await;
+                }
+                value.set(
+                    "created".into(),
+                    AgentValue::integer(ch.created.0.as_second()),
+                )?;
+                // Channels the bot cannot read (e.g. not a member) just get no ts
+                match slack::latest_message_ts(&session, &ch.id).await {
+                    Ok(Some(ts)) => {
+                        value.set("last_message_ts".into(), AgentValue::string(ts.to_string()))?
+                    }
+                    Ok(None) => {}
+                    Err(e) => error!("Failed to peek at history of {}: {}", ch.id, e),
+                }
+            }
+            channels.push_back(value);
+        }
+
+        self.output(ctx, PORT_CHANNELS, AgentValue::array(channels))
+            .await
+    }
+}
+
+fn slack_channel_to_agent_value(ch: &SlackChannelInfo) -> AgentValue {
+    let mut obj = im::HashMap::new();
+
+    obj.insert("id".into(), AgentValue::string(ch.id.to_string()));
+
+    if let Some(name) = &ch.name {
+        obj.insert("name".into(), AgentValue::string(name.clone()));
+    }
+
+    // Access flags fields directly (they are Option<bool>)
+    if let Some(is_private) = ch.flags.is_private {
+        obj.insert("is_private".into(), AgentValue::boolean(is_private));
+    }
+
+    if let Some(is_archived) = ch.flags.is_archived {
+        obj.insert("is_archived".into(), AgentValue::boolean(is_archived));
+    }
+
+    if let Some(is_member) = ch.flags.is_member {
+        obj.insert("is_member".into(), AgentValue::boolean(is_member));
+    }
+
+    if let Some(num_members) = ch.num_members {
+        obj.insert(
+            "num_members".into(),
+            AgentValue::integer(num_members as i64),
+        );
+    }
+
+    if let Some(ref topic) = ch.topic {
+        obj.insert("topic".into(), AgentValue::string(topic.value.clone()));
+    }
+
+    if let Some(ref purpose) = ch.purpose {
+        obj.insert("purpose".into(), AgentValue::string(purpose.value.clone()));
+    }
+
+    AgentValue::object(obj)
+}
+
+/// Agent for looking up a single channel via `conversations.info`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no channel
+///
+/// # Input
+/// - `channel`: Channel name or ID, or an object with `channel`
+///
+/// # Output
+/// - `channel`: Channel object containing `id`, `name`, `topic`, `purpose`,
+///   `num_members`, `created`, `creator` and flags such as `is_private`,
+///   `is_archived`, `is_member`, `is_general`, `is_shared`, `is_im`, `is_mpim`
+#[modular_agent(
+    title = "ChannelInfo",
+    category = CATEGORY,
+    inputs = [PORT_CHANNEL],
+    outputs = [PORT_CHANNEL],
+    string_config(name = CONFIG_CHANNEL),
+)]
+struct SlackChannelInfoAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackChannelInfoAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let channel = value_channel(&value, self.configs()?)?;
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let info = slack::fetch_channel_info(&session, &channel_id).await?;
+
+        self.output(ctx, PORT_CHANNEL, channel_info_to_value(&info))
+            .await
+    }
+}
+
+/// Reads the target channel from a string input, an object's `channel`, or the
+/// `channel` config.
+fn value_channel(value: &AgentValue, config: &AgentConfigs) -> Result<String, AgentError> {
+    value
+        .as_str()
+        .or_else(|| value.get_str(CONFIG_CHANNEL))
+        .map(String::from)
+        .filter(|c| !c.is_empty())
+        .or_else(|| non_empty_config(config, CONFIG_CHANNEL))
+        .ok_or_else(|| AgentError::InvalidValue("Channel not configured".to_string()))
+}
+
+fn channel_info_to_value(ch: &SlackChannelInfo) -> AgentValue {
+    let mut value = slack_channel_to_agent_value(ch);
+    let Some(obj) = value.as_object_mut() else {
+        return value;
+    };
+
+    obj.insert(
+        "created".into(),
+        AgentValue::integer(ch.created.0.as_second()),
+    );
+    if let Some(creator) = &ch.creator {
+        obj.insert("creator".into(), AgentValue::string(creator.to_string()));
+    }
+
+    let flags = [
+        ("is_channel", ch.flags.is_channel),
+        ("is_group", ch.flags.is_group),
+        ("is_im", ch.flags.is_im),
+        ("is_mpim", ch.flags.is_mpim),
+        ("is_general", ch.flags.is_general),
+        ("is_shared", ch.flags.is_shared),
+        ("is_org_shared", ch.flags.is_org_shared),
+    ];
+    for (name, flag) in flags {
+        if let Some(flag) = flag {
+            obj.insert(name.into(), AgentValue::boolean(flag));
+        }
+    }
+
+    value
+}
+
+/// Agent for creating a channel via `conversations.create`.
+///
+/// # Configuration
+/// - `name`: Default channel name, used when the input has no name
+/// - `is_private`: Create a private channel (default: false)
+///
+/// # Input
+/// - `value`: Channel name (with or without `#`), or an object with `name` and
+///   optionally `is_private`
+///
+/// # Output
+/// - `channel`: The new channel object, with its `id`, `name` and flags
+#[modular_agent(
+    title = "CreateChannel",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_CHANNEL],
+    string_config(name = CONFIG_NAME),
+    boolean_config(name = CONFIG_IS_PRIVATE),
+)]
+struct SlackCreateChannelAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackCreateChannelAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let name = value
+            .as_str()
+            .or_else(|| value.get_str(CONFIG_NAME))
+            .map(String::from)
+            .filter(|n| !n.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_NAME))
+            .ok_or_else(|| AgentError::InvalidValue("Channel name is required".to_string()))?;
+        let is_private = value
+            .get(CONFIG_IS_PRIVATE)
+            .and_then(|v| v.as_bool())
+            .unwrap_or_else(|| config.get_bool_or_default(CONFIG_IS_PRIVATE));
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let channel = slack::create_channel(&session, &name, is_private).await?;
+
+        self.output(ctx, PORT_CHANNEL, channel_info_to_value(&channel))
+            .await
+    }
+}
+
+/// Agent for archiving or unarchiving a channel via `conversations.archive` /
+/// `conversations.unarchive`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no channel
+/// - `action`: Default action, `archive` or `unarchive`, used when the input has no
+///   `action`
+///
+/// # Input
+/// - `channel`: Channel name or ID, or an object with `channel` and optionally `action`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel`, `action` on success
+#[modular_agent(
+    title = "ArchiveChannel",
+    category = CATEGORY,
+    inputs = [PORT_CHANNEL],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_ACTION, default = "archive"),
+)]
+struct SlackArchiveChannelAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackArchiveChannelAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = value_channel(&value, config)?;
+        let action = value
+            .get_str(CONFIG_ACTION)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_ACTION));
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        match action.as_str() {
+            "archive" => slack::archive_channel(&session, &channel_id).await?,
+            "unarchive" => slack::unarchive_channel(&session, &channel_id).await?,
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown archive action: {} (expected archive or unarchive)",
+                    other
+                )));
+            }
+        }
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "action".into() => AgentValue::string(action),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for joining or leaving a channel via `conversations.join` /
+/// `conversations.leave`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no channel
+/// - `action`: Default action, `join` or `leave`, used when the input has no `action`
+///
+/// # Input
+/// - `channel`: Channel name or ID, or an object with `channel` and optionally `action`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel`, `action` on success
+#[modular_agent(
+    title = "JoinChannel",
+    category = CATEGORY,
+    inputs = [PORT_CHANNEL],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_ACTION, default = "join"),
+)]
+struct SlackJoinChannelAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackJoinChannelAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = value_channel(&value, config)?;
+        let action = value
+            .get_str(CONFIG_ACTION)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_ACTION));
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        match action.as_str() {
+            "join" => {
+                slack::join_channel(&session, &channel_id).await?;
+            }
+            "leave" => slack::leave_channel(&session, &channel_id).await?,
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown channel action: {} (expected join or leave)",
+                    other
+                )));
+            }
+        }
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "action".into() => AgentValue::string(action),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for renaming a channel via `conversations.rename`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no channel
+/// - `name`: Default new name, used when the input has no `name`. `{name}` is replaced
+///   with the current name, so `{name}-resolved` appends a suffix.
+///
+/// # Input
+/// - `channel`: Channel name or ID, or an object with `channel` and optionally `name`
+///
+/// # Output
+/// - `channel`: The renamed channel object, with its `id`, new `name` and flags
+#[modular_agent(
+    title = "RenameChannel",
+    category = CATEGORY,
+    inputs = [PORT_CHANNEL],
+    outputs = [PORT_CHANNEL],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_NAME),
+)]
+struct SlackRenameChannelAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackRenameChannelAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = value_channel(&value, config)?;
+        let name = value
+            .get_str(CONFIG_NAME)
+            .map(String::from)
+            .filter(|n| !n.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_NAME))
+            .ok_or_else(|| AgentError::InvalidValue("New channel name is required".to_string()))?;
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        let name = if name.contains("{name}") {
+            let info = slack::fetch_channel_info(&session, &channel_id).await?;
+            name.replace("{name}", info.name.as_deref().unwrap_or_default())
+        } else {
+            name
+        };
+        let renamed = slack::rename_channel(&session, &channel_id, &name).await?;
+
+        self.output(ctx, PORT_CHANNEL, channel_info_to_value(&renamed))
+            .await
+    }
+}
+
+/// Agent for setting a channel's topic or purpose via `conversations.setTopic` /
+/// `conversations.setPurpose`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `field`: Default field, `topic` or `purpose`, used when the input has no `field`
+///
+/// # Input
+/// - `value`: The new text, or an object with `text` and optionally `channel` and
+///   `field`. Text longer than 250 characters is truncated.
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel`, `field` and the `text` that was set
+#[modular_agent(
+    title = "SetChannelTopic",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_FIELD, default = "topic"),
+)]
+struct SlackSetChannelTopicAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackSetChannelTopicAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = value
+            .get_str(CONFIG_CHANNEL)
+            .map(String::from)
+            .filter(|c| !c.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_CHANNEL))
+            .ok_or_else(|| AgentError::InvalidValue("Channel not configured".to_string()))?;
+        let field = value
+            .get_str(CONFIG_FIELD)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_FIELD));
+        // An empty text clears the topic, so only a missing one is an error
+        let Some(text) = value.as_str().or_else(|| value.get_str("text")) else {
+            return Err(AgentError::InvalidValue(
+                "Topic text is required".to_string(),
+            ));
+        };
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        let text = match field.as_str() {
+            "topic" => slack::set_channel_topic(&session, &channel_id, text).await?,
+            "purpose" => slack::set_channel_purpose(&session, &channel_id, text).await?,
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown channel field: {} (expected topic or purpose)",
+                    other
+                )));
+            }
+        };
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "field".into() => AgentValue::string(field),
+            "text".into() => AgentValue::string(text),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for inviting users to a channel via `conversations.invite`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+/// - `users`: Default comma-separated user IDs or email addresses, used when the input
+///   has no users
+///
+/// # Input
+/// - `value`: An array or comma-separated string of user IDs or email addresses, or an
+///   object with `users` and optionally `channel`
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel` and `users`, the invited user IDs
+#[modular_agent(
+    title = "InviteToChannel",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_USERS),
+)]
+struct SlackInviteToChannelAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackInviteToChannelAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = value
+            .get_str(CONFIG_CHANNEL)
+            .map(String::from)
+            .filter(|c| !c.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_CHANNEL))
+            .ok_or_else(|| AgentError::InvalidValue("Channel not configured".to_string()))?;
+        let mut users = if value.is_object() {
+            user_list(value.get(CONFIG_USERS))
+        } else {
+            user_list(Some(&value))
+        };
+        if users.is_empty() {
+            users = user_list(Some(&AgentValue::string(
+                config.get_string_or_default(CONFIG_USERS),
+            )));
+        }
+        if users.is_empty() {
+            return Err(AgentError::InvalidValue("No users to invite".to_string()));
+        }
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let mut user_ids = Vec::with_capacity(users.len());
+        for user in &users {
+            user_ids.push(slack::resolve_user_id(&session, user).await?);
+        }
+        slack::invite_to_channel(&session, &channel_id, &user_ids).await?;
+
+        let user_ids: Vector<AgentValue> = user_ids
+            .iter()
+            .map(|u| AgentValue::string(u.to_string()))
+            .collect();
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "users".into() => AgentValue::array(user_ids),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for opening a direct message or multi-person direct message via
+/// `conversations.open`.
+///
+/// # Configuration
+/// - `users`: Default comma-separated user IDs or email addresses, used when the input
+///   has no users
+///
+/// # Input
+/// - `value`: An array or comma-separated string of user IDs or email addresses, or an
+///   object with `users`. One user opens a DM, several open a group DM.
+///
+/// # Output
+/// - `channel`: Object containing `channel`, the conversation ID, and `users`, so it can
+///   be fed to the `channel` input of the Post agent
+#[modular_agent(
+    title = "OpenConversation",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_CHANNEL],
+    string_config(name = CONFIG_USERS),
+)]
+struct SlackOpenConversationAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackOpenConversationAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let mut users = if value.is_object() {
+            user_list(value.get(CONFIG_USERS))
+        } else {
+            user_list(Some(&value))
+        };
+        if users.is_empty() {
+            users = user_list(Some(&AgentValue::string(
+                config.get_string_or_default(CONFIG_USERS),
+            )));
+        }
+        if users.is_empty() {
+            return Err(AgentError::InvalidValue(
+                "No users to open a conversation with".to_string(),
+            ));
+        }
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let mut user_ids = Vec::with_capacity(users.len());
+        for user in &users {
+            user_ids.push(slack::resolve_user_id(&session, user).await?);
+        }
+        let channel_id = slack::open_conversation(&session, &user_ids).await?;
+
+        let user_ids: Vector<AgentValue> = user_ids
+            .iter()
+            .map(|u| AgentValue::string(u.to_string()))
+            .collect();
+        let result = AgentValue::object(hashmap! {
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "users".into() => AgentValue::array(user_ids),
+        });
+
+        self.output(ctx, PORT_CHANNEL, result).await
+    }
+}
+
+/// Agent for marking a channel as read up to a message via `conversations.mark`.
+///
+/// The read cursor belongs to a user, so this agent uses the user token.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no `channel`
+///
+/// # Input
+/// - `message`: Object with `channel` and `ts`, or an array of them (e.g. History
+///   output), in which case the channel is marked up to the newest message
+///
+/// # Output
+/// - `result`: Object containing `ok`, `channel`, `ts` on success
+#[modular_agent(
+    title = "MarkRead",
+    category = CATEGORY,
+    inputs = [PORT_MESSAGE],
+    outputs = [PORT_RESULT],
+    string_config(name = CONFIG_CHANNEL),
+)]
+struct SlackMarkReadAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackMarkReadAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let newest = match &value {
+            AgentValue::Array(arr) => arr
+                .iter()
+                .filter(|msg| msg.get_str("ts").is_some_and(|ts| !ts.is_empty()))
+                .max_by_key(|msg| ts_sort_key(msg.get_str("ts").unwrap_or_default())),
+            _ => Some(&value),
+        };
+        let Some(ts) = newest
+            .and_then(|msg| msg.get_str("ts"))
+            .filter(|ts| !ts.is_empty())
+            .map(String::from)
+        else {
+            return Err(AgentError::InvalidValue(
+                "Message ts is required".to_string(),
+            ));
+        };
+        let channel = newest
+            .and_then(|msg| msg.get_str(CONFIG_CHANNEL))
+            .map(String::from)
+            .filter(|c| !c.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_CHANNEL))
+            .ok_or_else(|| AgentError::InvalidValue("Channel not configured".to_string()))?;
+
+        let token = get_user_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+
+        slack::mark_read(&session, &channel_id, &ts.clone().into()).await?;
+
+        let result = AgentValue::object(hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "channel".into() => AgentValue::string(channel_id.to_string()),
+            "ts".into() => AgentValue::string(ts),
+        });
+
+        self.output(ctx, PORT_RESULT, result).await
+    }
+}
+
+/// Agent for listing a channel's members via `conversations.members`.
+///
+/// # Configuration
+/// - `channel`: Default channel name or ID, used when the input has no channel
+/// - `include_profiles`: Output a profile object per member instead of a user ID,
+///   joined from a single paged `users.list` (default: false)
+///
+/// # Input
+/// - `channel`: Channel name or ID, or an object with `channel`
+///
+/// # Output
+/// - `members`: Array of user IDs, or with `include_profiles`, user objects containing
+///   `id`, `name`, `display_name`, `real_name`, `email`, `title`, `tz`, `is_bot`, etc.
+#[modular_agent(
+    title = "Members",
+    category = CATEGORY,
+    inputs = [PORT_CHANNEL],
+    outputs = [PORT_MEMBERS],
+    string_config(name = CONFIG_CHANNEL),
+    boolean_config(name = CONFIG_INCLUDE_PROFILES),
+)]
+struct SlackMembersAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackMembersAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let channel = value_channel(&value, config)?;
+        let include_profiles = config.get_bool_or_default(CONFIG_INCLUDE_PROFILES);
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let channel_id = slack::resolve_channel_id(&session, &channel).await?;
+        let members = slack::fetch_channel_members(&session, &channel_id).await?;
+
+        if !include_profiles {
+            let values = members
+                .iter()
+                .map(|member| AgentValue::string(member.to_string()))
+                .collect();
+            return self
+                .output(ctx, PORT_MEMBERS, AgentValue::array(values))
+                .await;
+        }
+
+        // One paged users.list instead of a users.info call per member
+        let users: HashMap<SlackUserId, SlackUser> = slack::list_users(&session, None)
+            .await?
+            .into_iter()
+            .map(|user| (user.id.clone(), user))
+            .collect();
+        let values = members
+            .iter()
+            .map(|member| match users.get(member) {
+                Some(user) => user_to_value(user),
+                // e.g. users from another workspace in a shared channel
+                None => AgentValue::object(hashmap! {
+                    "id".into() => AgentValue::string(member.to_string()),
+                }),
+            })
+            .collect();
+
+        self.output(ctx, PORT_MEMBERS, AgentValue::array(values))
+            .await
+    }
+}
+
+/// Agent for listing the workspace's users via `users.list`.
+///
+/// # Configuration
+/// - `limit`: Maximum number of users to fetch (default: 0, everyone)
+/// - `exclude_bots`: Leave out bots and apps (default: false)
+/// - `exclude_deleted`: Leave out deactivated users (default: false)
+///
+/// # Input
+/// - `trigger`: Any value triggers fetching the user list
+///
+/// # Output
+/// - `values`: Array of user objects containing `id`, `name`, `display_name`,
+///   `real_name`, `email`, `is_bot`, `deleted`, etc.
+#[modular_agent(
+    title = "Users",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_VALUES],
+    integer_config(name = CONFIG_LIMIT),
+    boolean_config(name = CONFIG_EXCLUDE_BOTS),
+    boolean_config(name = CONFIG_EXCLUDE_DELETED),
+)]
+struct SlackUsersAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackUsersAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        _value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let limit = config.get_integer_or_default(CONFIG_LIMIT);
+        let exclude_bots = config.get_bool_or_default(CONFIG_EXCLUDE_BOTS);
+        let exclude_deleted = config.get_bool_or_default(CONFIG_EXCLUDE_DELETED);
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        // Filtering happens after the limit, so `limit` bounds the API calls.
+        let users = slack::list_users(&session, (limit > 0).then_some(limit as usize)).await?;
+        let users: Vector<AgentValue> = users
+            .iter()
+            .filter(|u| {
+                // Slackbot has is_bot false but a fixed ID.
+                let is_bot = u.flags.is_bot.unwrap_or(false)
+                    || u.flags.is_app_user.unwrap_or(false)
+                    || u.id.0 == "USLACKBOT";
+                !(exclude_bots && is_bot)
+            })
+            .filter(|u| !(exclude_deleted && u.deleted.unwrap_or(false)))
+            .map(user_to_value)
+            .collect();
+
+        self.output(ctx, PORT_VALUES, AgentValue::array(users))
+            .await
+    }
+}
+
+/// Agent for finding a user by email address via `users.lookupByEmail`.
+///
+/// # Input
+/// - `value`: Email address, or an object with `email`
+///
+/// # Output
+/// - `value`: User object containing `id`, `name`, `display_name`, `real_name`,
+///   `email`, `is_bot`, `deleted`, etc.
+#[modular_agent(
+    title = "LookupUser",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+)]
+struct SlackLookupUserAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackLookupUserAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let email = value
+            .as_str()
+            .or_else(|| value.get_str("email"))
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .ok_or_else(|| AgentError::InvalidValue("Email address is required".to_string()))?;
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let user = slack::lookup_user_by_email(&session, email).await?;
+        self.output(ctx, PORT_VALUE, user_to_value(&user)).await
+    }
+}
+
+/// Agent for checking whether a user is active or away via `users.getPresence`.
+///
+/// # Configuration
+/// - `user`: Default user ID or email address, used when the input has no user
+///
+/// # Input
+/// - `value`: User ID or email address, or an object with `user` or `id`
+///
+/// # Output
+/// - `value`: Object containing `user`, `presence` (`active` or `away`), `active`,
+///   and `auto_away`, `manual_away`, `last_activity` when Slack reports them
+#[modular_agent(
+    title = "Presence",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+    string_config(name = CONFIG_USER),
+)]
+struct SlackPresenceAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackPresenceAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let user = value
+            .as_str()
+            .or_else(|| value.get_str(CONFIG_USER))
+            .or_else(|| value.get_str("id"))
+            .map(String::from)
+            .filter(|u| !u.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_USER))
+            .ok_or_else(|| AgentError::InvalidValue("User not configured".to_string()))?;
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let user_id = slack::resolve_user_id(&session, &user).await?;
+        let presence = slack::fetch_presence(&session, &user_id).await?;
+
+        let mut obj = hashmap! {
+            "user".into() => AgentValue::string(user_id.to_string()),
+            "active".into() => AgentValue::boolean(presence.presence == "active"),
+            "presence".into() => AgentValue::string(presence.presence),
+        };
+        let flags = [
+            ("auto_away", presence.auto_away),
+            ("manual_away", presence.manual_away),
+        ];
+        for (name, flag) in flags {
+            if let Some(flag) = flag {
+                obj.insert(name.into(), AgentValue::boolean(flag));
+            }
+        }
+        if let Some(last_activity) = &presence.last_activity {
+            obj.insert(
+                "last_activity".into(),
+                AgentValue::integer(last_activity.0.as_second()),
+            );
+        }
+
+        self.output(ctx, PORT_VALUE, AgentValue::object(obj)).await
+    }
+}
+
+/// Agent for listing user groups via `usergroups.list`.
+///
+/// # Configuration
+/// - `usergroup`: Only return the group with this handle (e.g. `oncall` or `@oncall`)
+///
+/// # Input
+/// - `trigger`: Any value triggers fetching the user groups. An object may carry
+///   `usergroup`, which overrides the configuration.
+///
+/// # Output
+/// - `values`: Array of user group objects containing `id`, `handle`, `name`,
+///   `description`, `user_count` and `mention` (`<!subteam^ID>`)
+#[modular_agent(
+    title = "UserGroups",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_VALUES],
+    string_config(name = CONFIG_USERGROUP),
+)]
+struct SlackUserGroupsAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackUserGroupsAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let handle = value
+            .get_str(CONFIG_USERGROUP)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_USERGROUP));
+        let handle = handle.trim().trim_start_matches('@');
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let groups: Vector<AgentValue> = slack::list_usergroups(&session)
+            .await?
+            .iter()
+            .filter(|g| handle.is_empty() || g.handle == handle)
+            .map(usergroup_to_value)
+            .collect();
+
+        self.output(ctx, PORT_VALUES, AgentValue::array(groups))
+            .await
+    }
+}
+
+/// Agent for reading and updating the members of a user group via
+/// `usergroups.users.list` / `usergroups.users.update`.
+///
+/// # Configuration
+/// - `usergroup`: Default user group handle (e.g. `oncall`) or ID, used when the input
+///   has no `usergroup`
+/// - `action`: Default action, used when the input has no `action`: `list` (default),
+///   `set` (replace the members with `users`), `add` or `remove`
+///
+/// # Input
+/// - `value`: Object with `users` (an array or comma-separated string of user IDs or
+///   email addresses) and optionally `usergroup` and `action`. Any value triggers `list`.
+///
+/// # Output
+/// - `value`: Object containing `usergroup` (the ID), `action` and `users`, the
+///   group's members after the action
+#[modular_agent(
+    title = "UserGroupMembers",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+    string_config(name = CONFIG_USERGROUP),
+    string_config(name = CONFIG_ACTION, default = "list"),
+)]
+struct SlackUserGroupMembersAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackUserGroupMembersAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let usergroup = value
+            .get_str(CONFIG_USERGROUP)
+            .map(String::from)
+            .filter(|g| !g.is_empty())
+            .or_else(|| non_empty_config(config, CONFIG_USERGROUP))
+            .ok_or_else(|| AgentError::InvalidValue("Usergroup not configured".to_string()))?;
+        let action = value
+            .get_str(CONFIG_ACTION)
+            .map(String::from)
+            .unwrap_or_else(|| config.get_string_or_default(CONFIG_ACTION));
+        let users = user_list(value.get(CONFIG_USERS));
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let usergroup_id = slack::resolve_usergroup_id(&session, &usergroup).await?;
+        let mut user_ids = Vec::with_capacity(users.len());
+        for user in &users {
+            user_ids.push(slack::resolve_user_id(&session, user).await?);
+        }
+
+        let members = match action.as_str() {
+            "list" => slack::fetch_usergroup_users(&session, &usergroup_id).await?,
+            "set" | "add" | "remove" => {
+                let mut members = if action == "set" {
+                    Vec::new()
+                } else {
+                    slack::fetch_usergroup_users(&session, &usergroup_id).await?
+                };
+                match action.as_str() {
+                    "remove" => members.retain(|m| !user_ids.contains(m)),
+                    _ => {
+                        for user_id in user_ids {
+                            if !members.contains(&user_id) {
+                                members.push(user_id);
+                            }
+                        }
+                    }
+                }
+                if members.is_empty() {
+                    return Err(AgentError::InvalidValue(
+                        "A user group must keep at least one member".to_string(),
+                    ));
+                }
+                slack::update_usergroup_users(&session, &usergroup_id, &members).await?;
+                members
+            }
+            other => {
+                return Err(AgentError::InvalidValue(format!(
+                    "Unknown usergroup action: {} (expected list, set, add or remove)",
+                    other
+                )));
+            }
+        };
+
+        let members: Vector<AgentValue> = members
+            .iter()
+            .map(|m| AgentValue::string(m.to_string()))
+            .collect();
+        let result = AgentValue::object(hashmap! {
+            "usergroup".into() => AgentValue::string(usergroup_id.to_string()),
+            "action".into() => AgentValue::string(action),
+            "users".into() => AgentValue::array(members),
+        });
+        self.output(ctx, PORT_VALUE, result).await
+    }
+}
+
+/// Reads user IDs or email addresses from an array or a comma-separated string.
+fn user_list(value: Option<&AgentValue>) -> Vec<String> {
+    match value {
+        Some(AgentValue::Array(arr)) => arr
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect(),
+        Some(AgentValue::String(users)) => users
+            .split(',')
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn usergroup_to_value(group: &SlackUserGroup) -> AgentValue {
+    let mut obj = hashmap! {
+        "id".into() => AgentValue::string(group.id.to_string()),
+        "handle".into() => AgentValue::string(group.handle.clone()),
+        "name".into() => AgentValue::string(group.name.clone()),
+        "user_count".into() => AgentValue::integer(group.user_count as i64),
+        "mention".into() => AgentValue::string(format!("<!subteam^{}>", group.id)),
+    };
+    if let Some(description) = group.description.as_ref().filter(|d| !d.is_empty()) {
+        obj.insert(
+            "description".into(),
+            AgentValue::string(description.clone()),
+        );
+    }
+    AgentValue::object(obj)
+}
+
+/// Agent for checking users' Do Not Disturb status via `dnd.info` / `dnd.teamInfo`.
+///
+/// # Configuration
+/// - `user`: Default user ID or email address, used when the input has no user
+///
+/// # Input
+/// - `value`: User ID or email address, an array of them, or an object with `user`
+///   (an ID, an email or an array)
+///
+/// # Output
+/// - `value`: Object containing `user`, `in_dnd` (whether notifications are paused
+///   now), `available_at` (Unix time they resume, when paused), `dnd_enabled`,
+///   `next_dnd_start_ts`, `next_dnd_end_ts`, `snooze_enabled`, `snooze_endtime`.
+///   An array of these when the input has several users.
+#[modular_agent(
+    title = "Dnd",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+    string_config(name = CONFIG_USER),
+)]
+struct SlackDndAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackDndAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let users_value = value.get(CONFIG_USER).unwrap_or(&value);
+        let (users, several): (Vec<String>, bool) = match users_value {
+            AgentValue::Array(arr) => (
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(String::from)
+                    .collect(),
+                true,
+            ),
+            AgentValue::String(user) if !user.is_empty() => (vec![user.to_string()], false),
+            _ => (
+                non_empty_config(config, CONFIG_USER).into_iter().collect(),
+                false,
+            ),
+        };
+        if users.is_empty() {
+            return Err(AgentError::InvalidValue("User not configured".to_string()));
+        }
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let mut user_ids = Vec::with_capacity(users.len());
+        for user in &users {
+            user_ids.push(slack::resolve_user_id(&session, user).await?);
+        }
+        let now = chrono::Utc::now().timestamp();
+
+        if !several {
+            let status = slack::fetch_dnd_info(&session, &user_ids[0]).await?;
+            return self
+                .output(ctx, PORT_VALUE, dnd_to_value(&user_ids[0], &status, now)?)
+                .await;
+        }
+
+        let mut statuses = Vector::new();
+        for chunk in user_ids.chunks(50) {
+            let team_info = slack::fetch_dnd_team_info(&session, chunk).await?;
+            for user_id in chunk {
+                let status = team_info.get(user_id.as_ref()).cloned().unwrap_or_default();
+                statuses.push_back(dnd_to_value(user_id, &status, now)?);
+            }
+        }
+        self.output(ctx, PORT_VALUE, AgentValue::array(statuses))
+            .await
+    }
+}
+
+fn dnd_to_value(
+    user_id: &SlackUserId,
+    status: &slack::DndStatus,
+    now: i64,
+) -> Result<AgentValue, AgentError> {
+    let mut value = AgentValue::from_serialize(status)?;
+    value.set("user".into(), AgentValue::string(user_id.to_string()))?;
+    value.set("in_dnd".into(), AgentValue::boolean(status.is_active(now)))?;
+    if let Some(available_at) = status.available_at(now) {
+        value.set("available_at".into(), AgentValue::integer(available_at))?;
+    }
+    Ok(value)
+}
+
+/// Agent for resolving a `bot_id` (as seen on History messages) to the app behind it
+/// via `bots.info`.
+///
+/// # Input
+/// - `value`: Bot ID, or an object with `bot_id`
+///
+/// # Output
+/// - `value`: Object containing `id`, `name`, `app_id`, `user_id`, `icon` (the largest
+///   icon URL) and `icons` (`image_36`, `image_48`, `image_72`, ...). Results are
+///   cached for the lifetime of the agent.
+#[modular_agent(
+    title = "BotInfo",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+)]
+struct SlackBotInfoAgent {
+    data: AgentData,
+    bots: HashMap<String, AgentValue>,
+}
+
+#[async_trait]
+impl AsAgent for SlackBotInfoAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+            bots: HashMap::new(),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let bot_id = value
+            .as_str()
+            .or_else(|| value.get_str("bot_id"))
+            .filter(|b| !b.is_empty())
+            .ok_or_else(|| AgentError::InvalidValue("bot_id is required".to_string()))?
+            .to_string();
+
+        if let Some(bot) = self.bots.get(&bot_id) {
+            let bot = bot.clone();
+            return self.output(ctx, PORT_VALUE, bot).await;
+        }
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let info = slack::fetch_bot_info(&session, &bot_id.clone().into()).await?;
+
+        let mut obj = hashmap! {
+            "id".into() => AgentValue::string(bot_id.clone()),
+            "name".into() => AgentValue::string(info.name),
+            "app_id".into() => AgentValue::string(info.app_id),
+        };
+        if let Some(user_id) = info.user_id {
+            obj.insert("user_id".into(), AgentValue::string(user_id));
+        }
+        if let Some(icons) = info.icons.filter(|i| !i.resolutions.is_empty()) {
+            if let Some((_, url)) = icons.resolutions.iter().max_by_key(|(size, _)| *size) {
+                obj.insert("icon".into(), AgentValue::string(url.clone()));
+            }
+            let icons = icons
+                .resolutions
+                .into_iter()
+                .map(|(size, url)| (format!("image_{}", size), AgentValue::string(url)))
+                .collect();
+            obj.insert("icons".into(), AgentValue::object(icons));
+        }
+
+        let bot = AgentValue::object(obj);
+        self.bots.insert(bot_id, bot.clone());
+        self.output(ctx, PORT_VALUE, bot).await
+    }
+}
+
+fn user_to_value(user: &SlackUser) -> AgentValue {
+    let mut obj = im::HashMap::new();
+    obj.insert("id".into(), AgentValue::string(user.id.to_string()));
+    obj.insert(
+        "display_name".into(),
+        AgentValue::string(slack::user_display_name(user)),
+    );
+    if let Some(name) = &user.name {
+        obj.insert("name".into(), AgentValue::string(name.clone()));
+    }
+
+    let profile = user.profile.as_ref();
+    if let Some(real_name) = profile
+        .and_then(|p| p.real_name.clone())
+        .or_else(|| user.real_name.clone())
+    {
+        obj.insert("real_name".into(), AgentValue::string(real_name));
+    }
+    if let Some(email) = profile.and_then(|p| p.email.as_ref()) {
+        obj.insert("email".into(), AgentValue::string(email.to_string()));
+    }
+    if let Some(title) = profile
+        .and_then(|p| p.title.clone())
+        .filter(|t| !t.is_empty())
+    {
+        obj.insert("title".into(), AgentValue::string(title));
+    }
+    if let Some(tz) = &user.tz {
+        obj.insert("tz".into(), AgentValue::string(tz.clone()));
+    }
+
+    let flags = [
+        ("is_bot", user.flags.is_bot),
+        ("is_admin", user.flags.is_admin),
+        ("is_owner", user.flags.is_owner),
+        ("is_restricted", user.flags.is_restricted),
+        ("deleted", user.deleted),
+    ];
+    for (name, flag) in flags {
+        if let Some(flag) = flag {
+            obj.insert(name.into(), AgentValue::boolean(flag));
+        }
+    }
+
+    AgentValue::object(obj)
+}
+
+/// Agent for listing files via `files.list`.
+///
+/// # Configuration
+/// - `channel`: Only files shared in this channel (name or ID)
+/// - `user`: Only files uploaded by this user ID
+/// - `types`: Comma-separated Slack file categories: `images`, `pdfs`, `snippets`,
+///   `spaces`, `gdocs`, `zips` (default: all)
+/// - `filetype`: Only files of these comma-separated file types, e.g. `csv,xlsx`
+/// - `oldest`, `latest`: Only files created in this time range, in the same formats as
+///   the History agent (Slack ts, RFC 3339 or relative such as `-24h`)
+/// - `limit`: Maximum number of files to return (default: 100)
+///
+/// # Input
+/// - `trigger`: Any value triggers the listing. An object may carry `channel`, `user`,
+///   `oldest` and `latest`, which override the configuration.
+///
+/// # Output
+/// - `values`: Array of file objects containing `id`, `name`, `title`, `mimetype`,
+///   `filetype`, `url_private`, `permalink`, `user`, `created`, `size` (bytes)
+#[modular_agent(
+    title = "Files",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_VALUES],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_USER),
+    string_config(name = CONFIG_TYPES),
+    string_config(name = CONFIG_FILETYPE),
+    string_config(name = CONFIG_OLDEST),
+    string_config(name = CONFIG_LATEST),
+    integer_config(name = CONFIG_LIMIT, default = 100),
+)]
+struct SlackFilesAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackFilesAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let setting = |key: &str| {
+            value
+                .get_str(key)
+                .map(String::from)
+                .or_else(|| non_empty_config(config, key))
+                .filter(|v| !v.trim().is_empty())
+        };
+        let now = chrono::Utc::now();
+        let time_bound = |key: &str| {
+            setting(key)
+                .map(|t| slack::parse_history_time(&t, now))
+                .transpose()
+                .map(|ts| ts.and_then(|ts| ts.0.split('.').next()?.parse::<i64>().ok()))
+        };
+        let ts_from = time_bound(CONFIG_OLDEST)?;
+        let ts_to = time_bound(CONFIG_LATEST)?;
+        let limit = config.get_integer_or_default(CONFIG_LIMIT);
+        let limit = if limit <= 0 { 100 } else { limit as usize };
+        let filetypes: Vec<String> = config
+            .get_string_or_default(CONFIG_FILETYPE)
+            .split(',')
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let channel = match setting(CONFIG_CHANNEL) {
+            Some(channel) => Some(slack::resolve_channel_id(&session, &channel).await?),
+            None => None,
+        };
+        let request = SlackApiFilesListRequest::new()
+            .opt_channel(channel)
+            .opt_user(setting(CONFIG_USER).map(SlackUserId))
+            .opt_types(non_empty_config(config, CONFIG_TYPES))
+            .opt_ts_from(ts_from)
+            .opt_ts_to(ts_to);
+
+        let files = slack::list_files(&session, &request, limit, |file| {
+            filetypes.is_empty()
+                || file
+                    .filetype
+                    .as_ref()
+                    .is_some_and(|t| filetypes.contains(&t.to_string().to_lowercase()))
+        })
+        .await?;
+        let files: Vector<AgentValue> = files
+            .iter()
+            .map(|f| file_to_value(&f.file, f.size))
+            .collect();
+
+        self.output(ctx, PORT_VALUES, AgentValue::array(files))
+            .await
+    }
+}
+
+/// Agent for fetching a file's metadata and content.
+///
+/// # Input
+/// - `file`: File ID, `url_private` / `url_private_download` URL, or a file object
+///   (e.g. from the Files agent or a message's `files`) with `id` or `url_private`.
+///   The content is always fetched from the `url_private_download` that `files.info`
+///   returns, so other URLs are rejected
+///
+/// # Output
+/// - `file`: The file object (`id`, `name`, `title`, `mimetype`, `filetype`,
+///   `url_private`, `permalink`, `user`, `created`) plus `size` and the content as
+///   `base64`. Images also carry the decoded `image` when the `image` feature is enabled.
+#[modular_agent(
+    title = "File",
+    category = CATEGORY,
+    inputs = [PORT_FILE],
+    outputs = [PORT_FILE],
+)]
+struct SlackFileAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackFileAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let file = value
+            .as_str()
+            .or_else(|| value.get_str("id"))
+            .or_else(|| value.get_str("url_private_download"))
+            .or_else(|| value.get_str("url_private"))
+            .filter(|f| !f.is_empty())
+            .ok_or_else(|| {
+                AgentError::InvalidValue("File ID or url_private is required".to_string())
+            })?;
+        let file_id = if file.starts_with("https://") {
+            slack::file_id_from_url(file).ok_or_else(|| {
+                AgentError::InvalidValue(format!(
+                    "Not a Slack file URL, pass the file ID instead: {}",
+                    file
+                ))
+            })?
+        } else {
+            file.into()
+        };
+
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let info = slack::fetch_file_info(&session, &file_id).await?;
+        let url = info
+            .file
+            .url_private_download
+            .as_ref()
+            .or(info.file.url_private.as_ref())
+            .map(|u| u.to_string())
+            .ok_or_else(|| {
+                AgentError::InvalidValue(format!("File {} has no download URL", file_id))
+            })?;
+        let mimetype = info.file.mimetype.as_ref().map(|m| m.0.clone());
+        let mut result = file_to_value(&info.file, info.size);
+
+        let bytes = slack::download_file(&url, &token.token_value.0).await?;
+        result.set("size".into(), AgentValue::integer(bytes.len() as i64))?;
+        result.set(
+            "base64".into(),
+            AgentValue::string(base64::engine::general_purpose::STANDARD.encode(&bytes)),
+        )?;
+        #[cfg(feature = "image")]
+        if mimetype.as_deref().is_some_and(is_decodable_image) {
+            result.set(
+                "image".into(),
+                AgentValue::image(PhotonImage::new_from_byteslice(bytes)),
+            )?;
+        }
+        #[cfg(not(feature = "image"))]
+        let _ = mimetype;
+
+        self.output(ctx, PORT_FILE, result).await
+    }
+}
+
+/// Whether a file with this MIME type can be decoded into a PhotonImage.
+#[cfg(feature = "image")]
+fn is_decodable_image(mimetype: &str) -> bool {
+    matches!(
+        mimetype,
+        "image/png" | "image/jpeg" | "image/gif" | "image/webp" | "image/bmp"
+    )
+}
+
+/// Agent for fetching workspace information via `team.info`.
+///
+/// # Input
+/// - `trigger`: Any value triggers the lookup
+///
+/// # Output
+/// - `value`: Object containing `id`, `name`, `domain`, `email_domain`, `url` (the
+///   workspace URL, e.g. `https://example.slack.com/`) and `icon` (the largest icon URL)
+#[modular_agent(
+    title = "TeamInfo",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_VALUE],
+)]
+struct SlackTeamInfoAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackTeamInfoAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        _value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let team = slack::fetch_team_info(&session).await?;
+
+        let mut obj = im::HashMap::new();
+        obj.insert("id".into(), AgentValue::string(team.id.to_string()));
+        if let Some(domain) = &team.domain {
+            obj.insert(
+                "url".into(),
+                AgentValue::string(format!("https://{}.slack.com/", domain)),
+            );
+        }
+        let icon = team.icon.as_ref().and_then(|icon| {
+            icon.images
+                .as_ref()
+                .and_then(|images| images.resolutions.iter().max_by_key(|(size, _)| *size))
+                .map(|(_, url)| url.clone())
+                .or_else(|| icon.image_original.clone())
+        });
+        let fields = [
+            ("name", team.name),
+            ("domain", team.domain),
+            ("email_domain", team.email_domain.filter(|d| !d.is_empty())),
+            ("icon", icon),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                obj.insert(key.into(), AgentValue::string(value));
+            }
+        }
+
+        self.output(ctx, PORT_VALUE, AgentValue::object(obj)).await
+    }
+}
+
+/// Agent for verifying the bot token via `auth.test`.
+///
+/// Useful as a startup health check: an invalid, expired or revoked token fails with
+/// a configuration error naming the Slack error code.
+///
+/// # Input
+/// - `trigger`: Any value triggers the check
+///
+/// # Output
+/// - `value`: Object containing `ok`, `user_id` (the bot user), `user`, `bot_id`,
+///   `team_id`, `team` and `url`
+#[modular_agent(
+    title = "AuthTest",
+    category = CATEGORY,
+    inputs = [PORT_TRIGGER],
+    outputs = [PORT_VALUE],
+)]
+struct SlackAuthTestAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackAuthTestAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        _value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+
+        let auth = slack::auth_test(&session).await?;
+
+        let mut obj = hashmap! {
+            "ok".into() => AgentValue::boolean(true),
+            "user_id".into() => AgentValue::string(auth.user_id.to_string()),
+            "team_id".into() => AgentValue::string(auth.team_id.to_string()),
+            "team".into() => AgentValue::string(auth.team),
+            "url".into() => AgentValue::string(auth.url.0.to_string()),
+        };
+        if let Some(user) = auth.user {
+            obj.insert("user".into(), AgentValue::string(user));
+        }
+        if let Some(bot_id) = auth.bot_id {
+            obj.insert("bot_id".into(), AgentValue::string(bot_id.to_string()));
+        }
+
+        self.output(ctx, PORT_VALUE, AgentValue::object(obj)).await
+    }
+}
+
+/// Agent for listening to Slack messages in real-time via Socket Mode.
+///
+/// This agent starts listening when activated and outputs messages as they arrive.
+/// Listeners with the same app token share one Socket Mode connection, and each
+/// applies its own filters to the events it receives.
+///
+/// # Configuration
+/// - `channel`: Optional comma-separated channel names (with or without `#`) or IDs to
+///   listen to. Names are resolved to IDs when the agent starts. If empty, listens to
+///   all channels.
+/// - `trigger`: Which messages to forward: `all` (default), `dm` (only direct messages
+///   to the bot), `mention` (only messages mentioning the bot, including `app_mention`
+///   events) or `dm_or_mention` (mentions plus every direct message to the bot)
+/// - `thread_mode`: `all` (default), `threads_only` (only replies in threads) or
+///   `top_level_only` (only messages posted to the channel itself)
+/// - `allowed_users`: Comma-separated user IDs or email addresses. If set, only
+///   messages and events from these users are forwarded.
+/// - `blocked_users`: Comma-separated user IDs or email addresses whose messages and
+///   events are dropped
+/// - `text_filter`: Regular expression the message text must match to be forwarded
+///   (e.g. `(?i)deploy|error`). Empty forwards every message.
+/// - `resolve_mentions`: Rewrite `<@U...>` mentions in the message text to
+///   `@Display Name`, looking users up with `users.info` (default: false). Names are
+///   cached for as long as the connection is open.
+///
+/// # Output
+/// - `value`: Slack Message objects containing `message`, `user`, `channel`, `ts`, `thread_ts`
+///   fields, plus `metadata` when the message carries any
+/// - `event`: Other workspace events as objects with a `type` field:
+///   `reaction_added` / `reaction_removed` with `reaction`, `user`, `item_user`,
+///   `channel`, `ts` (the reacted message) and `event_ts`; `channel_created`,
+///   `channel_rename`, `channel_archive`, `channel_unarchive` and `channel_deleted`
+///   with `channel`, plus `name` and `user` where Slack provides them; `file_shared`
+///   with `channel`, `file_id`, `user` (the uploader), `event_ts` and the file's
+///   `name`, `title`, `mimetype`, `filetype`, `url_private`, `permalink`, `created`,
+///   `size`;
+///   `pin_added` / `pin_removed` with `channel`, `user` (who pinned), `ts`, `text` and
+///   `item_user` of the pinned message (or `file_id`), and `event_ts`; `team_join`
+///   with `user` (the new member's ID) and the same profile fields as Slack/Users;
+///   `link_shared` with `channel`, `ts` of the message, `user`, `links` (`url` and
+///   `domain`), `unfurl_id`, `source` and `event_ts`, ready for Slack/Unfurl
+/// - `change`: Edits and deletions of messages, as objects with `subtype`
+///   (`message_changed` or `message_deleted`), `channel`, `ts` of the affected message,
+///   `user`, `previous_text`, and for edits the new `text`
+/// - `interaction`: Block Kit interactions. `block_actions` (button clicks, select
+///   choices, overflow menus) are output once per action with `action_id`, `block_id`,
+///   `action_type`, `value` (an array for multi-selects), `user`, `channel`, `ts` of the
+///   message, `response_url` and `trigger_id`. `view_submission` / `view_closed`
+///   (modals) with `user`, `view_id`, `callback_id`, `private_metadata` and `values`,
+///   the inputs' values keyed by `action_id`. `shortcut` (global shortcuts) with
+///   `callback_id`, `user` and `trigger_id`; `message_action` (message shortcuts) adds
+///   `channel`, `ts`, the target `message` (as Slack/History outputs it) and
+///   `response_url`
+/// - `raw`: Every push event, serialized in full (the `event_callback` envelope with
+///   `team_id`, `api_app_id`, `event`, `event_id`, `event_time`), unfiltered, for event
+///   types the other ports do not cover
+/// - `status`: Connection state as `{status}` objects: `connected` once Socket Mode is
+///   up, `disconnected` when it is lost or the agent stops, and `error` (with an
+///   `error` message) for failed connection attempts and listener errors. Lost
+///   connections are reopened with exponential backoff (1 second up to 5 minutes).
+///
+/// # Required Tokens
+/// - `SLACK_BOT_TOKEN`: Bot User OAuth Token (via global config or environment)
+/// - `SLACK_APP_TOKEN`: App-Level Token with `connections:write` scope (via global config or environment)
+#[modular_agent(
+    title = "Listener",
+    category = CATEGORY,
+    outputs = [PORT_VALUE, PORT_EVENT, PORT_CHANGE, PORT_INTERACTION, PORT_RAW, PORT_STATUS],
+    string_config(name = CONFIG_CHANNEL),
+    string_config(name = CONFIG_TRIGGER, default = "all"),
+    string_config(name = CONFIG_THREAD_MODE, default = "all"),
+    string_config(name = CONFIG_ALLOWED_USERS),
+    string_config(name = CONFIG_BLOCKED_USERS),
+    string_config(name = CONFIG_TEXT_FILTER),
+    boolean_config(name = CONFIG_RESOLVE_MENTIONS),
+    custom_global_config(name = CONFIG_SLACK_APP_TOKEN, type_ = "password", default = AgentValue::string(""), title = "Slack App Token"),
+)]
+struct SlackListenerAgent {
+    data: AgentData,
+    /// App token of the connection this Listener is subscribed to, while started.
+    app_token: Option<SlackApiToken>,
+}
+
+struct SlackListenerUserState {
+    ma: ModularAgent,
+    id: String,
+    /// Channel IDs to listen to, `None` for every channel.
+    channel_filter: Option<HashSet<SlackChannelId>>,
+    trigger: ListenerTrigger,
+    thread_mode: ThreadMode,
+    /// Users whose messages are forwarded, `None` for everyone.
+    allowed_users: Option<HashSet<SlackUserId>>,
+    /// Users whose messages are dropped.
+    blocked_users: HashSet<SlackUserId>,
+    /// Only messages whose text matches are forwarded.
+    text_filter: Option<Regex>,
+    resolve_mentions: bool,
+    bot_user_id: SlackUserId,
+    bot_token: String,
+    /// Recently forwarded mentions, since one arrives both as a message and as an
+    /// `app_mention` event when the app subscribes to both.
+    recent_mentions: Mutex<VecDeque<SlackTs>>,
+}
+
+impl SlackListenerUserState {
+    fn output(&self, port: &str, value: AgentValue) {
+        if let Err(e) = self.ma.try_send_agent_out(
+            self.id.clone(),
+            AgentContext::new(),
+            port.to_string(),
+            value,
+        ) {
+            error!("Failed to output {}: {}", port, e);
+        }
+    }
+
+    /// Outputs the connection state on the `status` port.
+    fn output_status(&self, status: &str, error: Option<&str>) {
+        let mut obj = hashmap! {
+            "status".into() => AgentValue::string(status),
+        };
+        if let Some(error) = error {
+            obj.insert("error".into(), AgentValue::string(error));
+        }
+        self.output(PORT_STATUS, AgentValue::object(obj));
+    }
+}
+
+/// Delay before the first reconnect; doubled after each failed attempt.
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Longest delay between reconnects. A connection that stayed up this long resets
+/// the delay.
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Socket Mode connections by app token. Slack caps concurrent connections per app,
+/// so every Listener using a token shares one connection.
+static SOCKET_CONNECTIONS: LazyLock<Mutex<HashMap<String, Arc<SocketConnection>>>> =
+    LazyLock::new(Default::default);
+
+/// A Socket Mode connection and the Listeners its events are delivered to.
+struct SocketConnection {
+    /// Subscribed Listeners by agent ID.
+    listeners: Mutex<HashMap<String, Arc<SlackListenerUserState>>>,
+    connected: AtomicBool,
+    /// Signalled when the connection fails, so it is reopened.
+    connection_lost: Notify,
+    shutdown_tx: mpsc::Sender<()>,
+    /// User ID to display name, filled for Listeners with `resolve_mentions`.
+    user_names: tokio::sync::Mutex<HashMap<String, String>>,
+}
+
+impl SocketConnection {
+    fn listeners(&self) -> Vec<Arc<SlackListenerUserState>> {
+        self.listeners
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Outputs `connected` unless already connected, since every socket says hello.
+    fn connected(&self) {
+        if !self.connected.swap(true, Ordering::SeqCst) {
+            for listener in self.listeners() {
+                listener.output_status("connected", None);
+            }
+        }
+    }
+
+    fn disconnected(&self) {
+        if self.connected.swap(false, Ordering::SeqCst) {
+            for listener in self.listeners() {
+                listener.output_status("disconnected", None);
+            }
+        }
+    }
+
+    fn error(&self, message: &str) {
+        error!("{}", message);
+        for listener in self.listeners() {
+            listener.output_status("error", Some(message));
+        }
+    }
+}
+
+/// Adds a Listener to the connection for `app_token`, opening the connection if no
+/// other Listener uses the token yet.
+fn subscribe_listener(app_token: &SlackApiToken, listener: SlackListenerUserState) {
+    let listener = Arc::new(listener);
+    let mut connections = SOCKET_CONNECTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(connection) = connections.get(&app_token.token_value.0) {
+        if connection.connected.load(Ordering::SeqCst) {
+            listener.output_status("connected", None);
+        }
+        connection
+            .listeners
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(listener.id.clone(), listener);
+        return;
+    }
+
+    let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
+    let connection = Arc::new(SocketConnection {
+        listeners: Mutex::new(HashMap::from([(listener.id.clone(), listener)])),
+        connected: AtomicBool::new(false),
+        connection_lost: Notify::new(),
+        shutdown_tx,
+        user_names: Default::default(),
+    });
+    connections.insert(app_token.token_value.0.clone(), connection.clone());
+    tokio::spawn(run_socket_mode(connection, app_token.clone(), shutdown_rx));
+}
+
+/// Removes a Listener from the connection for `app_token`, closing the connection
+/// once no Listener uses it.
+fn unsubscribe_listener(app_token: &SlackApiToken, id: &str) {
+    let mut connections = SOCKET_CONNECTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(connection) = connections.get(&app_token.token_value.0).cloned() else {
+        return;
+    };
+    let mut listeners = connection
+        .listeners
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(listener) = listeners.remove(id)
+        && connection.connected.load(Ordering::SeqCst)
+    {
+        listener.output_status("disconnected", None);
+    }
+    if listeners.is_empty() {
+        connections.remove(&app_token.token_value.0);
+        let _ = connection.shutdown_tx.try_send(());
+    }
+}
+
+/// Number of mention timestamps remembered to drop duplicate deliveries.
+const RECENT_MENTIONS: usize = 64;
+
+/// Which messages the Listener forwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ListenerTrigger {
+    All,
+    /// Only messages in a direct message with the bot.
+    Dm,
+    /// Only messages that mention the bot.
+    Mention,
+    /// Mentions, plus every message in a direct message with the bot.
+    DmOrMention,
+}
+
+impl ListenerTrigger {
+    /// Reads the `trigger` config.
+    fn from_config(config: &AgentConfigs) -> Result<Self, AgentError> {
+        let trigger = config.get_string_or_default(CONFIG_TRIGGER);
+        match trigger.trim().to_lowercase().as_str() {
+            "" | "all" => Ok(Self::All),
+            "dm" => Ok(Self::Dm),
+            "mention" => Ok(Self::Mention),
+            "dm_or_mention" => Ok(Self::DmOrMention),
+            other => Err(AgentError::InvalidConfig(format!(
+                "Unknown trigger: {} (expected all, dm, mention or dm_or_mention)",
+                other
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl AsAgent for SlackListenerAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+            app_token: None,
+        })
+    }
+
+    async fn start(&mut self) -> Result<(), AgentError> {
+        let client = Arc::new(get_client().clone());
+
+        let bot_token = get_token(self.ma())?;
+        let bot_session = client.open_session(&bot_token);
+        let bot_user_id = bot_session
+            .auth_test()
+            .await
+            .map_err(|e| AgentError::IoError(format!("Slack API error during auth_test: {}", e)))?
+            .user_id;
+
+        let config = self.configs()?;
+        // Names are resolved once here, so the filter compares IDs per event
+        let channels: Vec<String> = config
+            .get_string_or_default(CONFIG_CHANNEL)
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from)
+            .collect();
+        let channel_filter = if channels.is_empty() {
+            None
+        } else {
+            let mut ids = HashSet::with_capacity(channels.len());
+            for channel in &channels {
+                ids.insert(slack::resolve_channel_id(&bot_session, channel).await?);
+            }
+            Some(ids)
+        };
+
+        let trigger = ListenerTrigger::from_config(config)?;
+        let thread_mode = ThreadMode::from_config(config)?;
+        let allowed_users = config_user_ids(&bot_session, config, CONFIG_ALLOWED_USERS).await?;
+        let blocked_users = config_user_ids(&bot_session, config, CONFIG_BLOCKED_USERS).await?;
+        let text_filter = non_empty_config(config, CONFIG_TEXT_FILTER)
+            .map(|pattern| {
+                Regex::new(&pattern).map_err(|e| {
+                    AgentError::InvalidConfig(format!("Invalid text_filter {}: {}", pattern, e))
+                })
+            })
+            .transpose()?;
+
+        let app_token = get_app_token(self.ma())?;
+
+        let listener = SlackListenerUserState {
+            ma: self.ma().clone(),
+            id: self.id().to_string(),
+            channel_filter,
+            trigger,
+            thread_mode,
+            allowed_users: (!allowed_users.is_empty()).then_some(allowed_users),
+            blocked_users,
+            text_filter,
+            resolve_mentions: config.get_bool_or_default(CONFIG_RESOLVE_MENTIONS),
+            bot_user_id,
+            bot_token: bot_token.token_value.0.clone(),
+            recent_mentions: Mutex::new(VecDeque::with_capacity(RECENT_MENTIONS)),
+        };
+        subscribe_listener(&app_token, listener);
+        self.app_token = Some(app_token);
+
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), AgentError> {
+        if let Some(app_token) = self.app_token.take() {
+            unsubscribe_listener(&app_token, self.id());
+        }
+        Ok(())
+    }
+}
+
+/// Keeps a Socket Mode connection open until shutdown, reconnecting with exponential
+/// backoff when it fails.
+async fn run_socket_mode(
+    connection: Arc<SocketConnection>,
+    app_token: SlackApiToken,
+    mut shutdown_rx: mpsc::Receiver<()>,
+) {
+    let client = Arc::new(get_client().clone());
+    let listener_environment = Arc::new(
+        SlackClientEventsListenerEnvironment::new(client.clone())
+            .with_error_handler(listener_error_handler)
+            .with_user_state(connection.clone()),
+    );
+
+    let mut backoff = RECONNECT_INITIAL_BACKOFF;
+    loop {
+        // The socket listener retries a bad token forever without reporting it, so
+        // check the token first to surface the error.
+        let session = client.open_session(&app_token);
+        let opened = session
+            .apps_connections_open(&SlackApiAppsConnectionOpenRequest::new())
+            .await;
+
+        if let Err(e) = opened {
+            connection.error(&format!("Failed to open Socket Mode connection: {}", e));
+        } else {
+            let socket_mode_callbacks = SlackSocketModeListenerCallbacks::new()
+                .with_hello_events(|_, _, states| listener_connected(states))
+                .with_push_events(push_events_handler)
+                .with_interaction_events(interaction_events_handler);
+
+            let socket_mode_listener = SlackClientSocketModeListener::new(
+                &SlackClientSocketModeConfig::new(),
+                listener_environment.clone(),
+                socket_mode_callbacks,
+            );
+
+            if let Err(e) = socket_mode_listener.listen_for(&app_token).await {
+                connection.error(&format!("Socket mode listener failed to start: {}", e));
+            } else {
+                socket_mode_listener.start().await;
+                let started = Instant::now();
+
+                // Wait for shutdown signal instead of using serve() which sets its own Ctrl-C handler
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        socket_mode_listener.shutdown().await;
+                        return;
+                    }
+                    _ = connection.connection_lost.notified() => {
+                        socket_mode_listener.shutdown().await;
+                        connection.disconnected();
+                    }
+                }
+                if started.elapsed() >= RECONNECT_MAX_BACKOFF {
+                    backoff = RECONNECT_INITIAL_BACKOFF;
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = shutdown_rx.recv() => return,
+            _ = tokio::time::sleep(backoff) => {}
+        }
+        backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+    }
+}
+
+/// The connection an event arrived on.
+async fn socket_connection(states: &SlackClientEventsUserState) -> Option<Arc<SocketConnection>> {
+    let storage = states.read().await;
+    let connection = storage.get_user_state::<Arc<SocketConnection>>().cloned();
+    if connection.is_none() {
+        error!("SocketConnection not found in storage");
+    }
+    connection
+}
+
+/// Handles the `hello` each socket receives once connected.
+async fn listener_connected(states: SlackClientEventsUserState) {
+    if let Some(connection) = socket_connection(&states).await {
+        connection.connected();
+    }
+}
+
+/// Reports listener errors on the `status` port. Errors of the socket itself mean the
+/// connection is gone, so they also trigger a reconnect.
+fn listener_error_handler(
+    err: Box<dyn std::error::Error + Send + Sync>,
+    _client: Arc<SlackHyperClient>,
+    states: SlackClientEventsUserState,
+) -> HttpStatusCode {
+    // Called synchronously; the state is only written when the connection opens
+    if let Ok(storage) = states.try_read()
+        && let Some(connection) = storage.get_user_state::<Arc<SocketConnection>>()
+    {
+        connection.error(&format!("Slack listener error: {}", err));
+        if matches!(
+            err.downcast_ref::<SlackClientError>(),
+            Some(SlackClientError::SocketModeProtocolError(_))
+        ) {
+            connection.connection_lost.notify_one();
+        }
+    } else {
+        error!("Slack listener error: {}", err);
+    }
+    HttpStatusCode::BAD_REQUEST
+}
+
+async fn push_events_handler(
+    event: SlackPushEventCallback,
+    _client: Arc<SlackHyperClient>,
+    states: SlackClientEventsUserState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(connection) = socket_connection(&states).await else {
+        return Ok(());
+    };
+    let listeners = connection.listeners();
+
+    output_raw_event(&listeners, &event);
+
+    match event.event {
+        SlackEventCallbackBody::Message(msg_event) => {
+            output_listener_message(&connection, &listeners, msg_event, false).await
+        }
+        SlackEventCallbackBody::AppMention(mention) => {
+            let msg_event = app_mention_to_message(mention);
+            output_listener_message(&connection, &listeners, msg_event, true).await
+        }
+        other => output_listener_event(&listeners, &other).await,
+    }
+
+    Ok(())
+}
+
+/// Outputs a message on the `value` port of every Listener whose settings accept it,
+/// or on the `change` port for edits and deletions.
+async fn output_listener_message(
+    connection: &SocketConnection,
+    listeners: &[Arc<SlackListenerUserState>],
+    msg_event: SlackMessageEvent,
+    is_app_mention: bool,
+) {
+    if matches!(
+        msg_event.subtype,
+        Some(SlackMessageEventType::MessageChanged | SlackMessageEventType::MessageDeleted)
+    ) {
+        for state in listeners {
+            output_message_change(state, &msg_event);
+        }
+        return;
+    }
+
+    let accepting: Vec<&Arc<SlackListenerUserState>> = listeners
+        .iter()
+        .filter(|state| listener_accepts_message(state, &msg_event, is_app_mention))
+        .collect();
+    if accepting.is_empty() {
+        return;
+    }
+
+    // Download image if present, once for every Listener
+    #[cfg(feature = "image")]
+    let image = download_first_image(&msg_event, &accepting[0].bot_token).await;
+    #[cfg(not(feature = "image"))]
+    let image: Option<PhotonImage> = None;
+
+    let Some(message) = slack_push_message_to_agent_value(&msg_event, image) else {
+        return;
+    };
+    let resolved = match accepting.iter().find(|state| state.resolve_mentions) {
+        Some(state) => {
+            Some(resolve_message_mentions(connection, message.clone(), &state.bot_token).await)
+        }
+        None => None,
+    };
+    for state in accepting {
+        match &resolved {
+            Some(resolved) if state.resolve_mentions => state.output(PORT_VALUE, resolved.clone()),
+            _ => state.output(PORT_VALUE, message.clone()),
+        }
+    }
+}
+
+/// Rewrites `<@U...>` mentions in a Listener message to `@Display Name`. Users that
+/// cannot be looked up keep their raw mention.
+async fn resolve_message_mentions(
+    connection: &SocketConnection,
+    mut value: AgentValue,
+    bot_token: &str,
+) -> AgentValue {
+    let Some(message) = value.get_mut("message").and_then(|m| m.as_message_mut()) else {
+        return value;
+    };
+    let user_ids = mrkdwn::mentioned_user_ids(&message.content);
+    if user_ids.is_empty() {
+        return value;
+    }
+
+    let token = SlackApiToken::new(SlackApiTokenValue(bot_token.to_string()));
+    let session = get_client().open_session(&token);
+    let mut user_names = connection.user_names.lock().await;
+    slack::resolve_user_names(&session, user_ids, &mut user_names).await;
+    message.content = mrkdwn::replace_user_mentions(&message.content, &user_names);
+    value
+}
+
+/// Whether a Listener's filters and trigger let a message through.
+fn listener_accepts_message(
+    state: &SlackListenerUserState,
+    msg_event: &SlackMessageEvent,
+    is_app_mention: bool,
+) -> bool {
+    if !listener_channel_allowed(state, msg_event.origin.channel.as_ref())
+        || !listener_user_allowed(state, msg_event.sender.user.as_ref())
+    {
+        return false;
+    }
+
+    // Check if bot's own message
+    if let Some(ref user) = msg_event.sender.user
+        && user == &state.bot_user_id
+    {
+        return false;
+    }
+
+    if !state.thread_mode.accepts(msg_event) {
+        return false;
+    }
+
+    if let Some(filter) = &state.text_filter {
+        let text = msg_event.content.as_ref().and_then(|c| c.text.as_deref());
+        if !filter.is_match(text.unwrap_or_default()) {
+            return false;
+        }
+    }
+
+    listener_triggered(state, msg_event, is_app_mention)
+}
+
+/// Outputs the whole push event, as Slack sent it, on the Listeners' `raw` port.
+fn output_raw_event(listeners: &[Arc<SlackListenerUserState>], event: &SlackPushEventCallback) {
+    if listeners.is_empty() {
+        return;
+    }
+    let value = match AgentValue::from_serialize(event) {
+        Ok(value) => value,
+        Err(e) => {
+            error!("Failed to serialize event: {}", e);
+            return;
+        }
+    };
+    for state in listeners {
+        state.output(PORT_RAW, value.clone());
+    }
+}
+
+/// Outputs a non-message event on the `event` port of every Listener, if it is one
+/// the Listener models and its channel passes the Listener's channel filter.
+async fn output_listener_event(
+    listeners: &[Arc<SlackListenerUserState>],
+    event: &SlackEventCallbackBody,
+) {
+    if let SlackEventCallbackBody::ChannelRename(rename) = event {
+        slack::forget_channel_id(&rename.channel.id);
+    }
+    let Some((channel, mut value)) = listener_event_to_value(event) else {
+        return;
+    };
+
+    let user = value.get_str("user").map(SlackUserId::from);
+    let accepting: Vec<&Arc<SlackListenerUserState>> = listeners
+        .iter()
+        .filter(|state| {
+            listener_channel_allowed(state, channel.as_ref())
+                && listener_user_allowed(state, user.as_ref())
+        })
+        .collect();
+    let Some(first) = accepting.first() else {
+        return;
+    };
+
+    if let SlackEventCallbackBody::FileShared(e) = event {
+        add_shared_file_details(&mut value, &e.file_id, first.bot_token.clone()).await;
+    }
+
+    for state in accepting {
+        state.output(PORT_EVENT, value.clone());
+    }
+}
+
+/// Adds the name, mimetype and other details of a shared file (`files.info`) to its
+/// `file_shared` event. The event is output without them if the lookup fails.
+async fn add_shared_file_details(value: &mut AgentValue, file_id: &SlackFileId, bot_token: String) {
+    let token = SlackApiToken::new(SlackApiTokenValue(bot_token));
+    let session = get_client().open_session(&token);
+    let file = match slack::fetch_file_info(&session, file_id).await {
+        Ok(file) => file,
+        Err(e) => {
+            error!("Failed to fetch shared file {}: {}", file_id, e);
+            return;
+        }
+    };
+    let (Some(obj), AgentValue::Object(details)) =
+        (value.as_object_mut(), file_to_value(&file.file, file.size))
+    else {
+        return;
+    };
+    for (key, detail) in details.iter() {
+        if key != "id" && key != "user" {
+            obj.insert(key.clone(), detail.clone());
+        }
+    }
+}
+
+/// Outputs a `message_changed` or `message_deleted` event on the Listener's `change`
+/// port. Edits that leave the text as it was, such as added link previews, are
+/// dropped.
+fn output_message_change(state: &SlackListenerUserState, msg: &SlackMessageEvent) {
+    let text = |edited: &Option<SlackMessageEventEdited>| {
+        edited
+            .as_ref()
+            .and_then(|m| m.content.as_ref())
+            .and_then(|c| c.text.clone())
+    };
+    let previous_text = text(&msg.previous_message);
+    let new_text = text(&msg.message);
+    let deleted = msg.subtype == Some(SlackMessageEventType::MessageDeleted);
+    if !deleted && new_text == previous_text {
+        return;
+    }
+
+    let Some(edited) = msg.message.as_ref().or(msg.previous_message.as_ref()) else {
+        return;
+    };
+    let user = edited.sender.user.as_ref();
+    if !listener_channel_allowed(state, msg.origin.channel.as_ref())
+        || !listener_user_allowed(state, user)
+        || user == Some(&state.bot_user_id)
+    {
+        return;
+    }
+
+    let ts = msg.deleted_ts.as_ref().unwrap_or(&edited.ts);
+    let subtype = if deleted {
+        "message_deleted"
+    } else {
+        "message_changed"
+    };
+    let mut obj = hashmap! {
+        "subtype".into() => AgentValue::string(subtype),
+        "ts".into() => AgentValue::string(ts.to_string()),
+        "event_ts".into() => AgentValue::string(msg.origin.ts.to_string()),
+    };
+    if let Some(channel) = &msg.origin.channel {
+        obj.insert("channel".into(), AgentValue::string(channel.to_string()));
+    }
+    if let Some(user) = user {
+        obj.insert("user".into(), AgentValue::string(user.to_string()));
+    }
+    if let Some(previous_text) = previous_text {
+        obj.insert("previous_text".into(), AgentValue::string(previous_text));
+    }
+    if let Some(text) = new_text.filter(|_| !deleted) {
+        obj.insert("text".into(), AgentValue::string(text));
+    }
+
+    state.output(PORT_CHANGE, AgentValue::object(obj));
+}
+
+/// Converts a non-message event to the Listener's `event` output, along with the
+/// channel it happened in.
+fn listener_event_to_value(
+    event: &SlackEventCallbackBody,
+) -> Option<(Option<SlackChannelId>, AgentValue)> {
+    match event {
+        SlackEventCallbackBody::ReactionAdded(e) => Some(reaction_event_to_value(
+            "reaction_added",
+            &e.reaction,
+            &e.user,
+            e.item_user.as_ref(),
+            &e.item,
+            &e.event_ts,
+        )),
+        SlackEventCallbackBody::ReactionRemoved(e) => Some(reaction_event_to_value(
+            "reaction_removed",
+            &e.reaction,
+            &e.user,
+            e.item_user.as_ref(),
+            &e.item,
+            &e.event_ts,
+        )),
+        SlackEventCallbackBody::ChannelCreated(e) => {
+            let mut obj = channel_event_object("channel_created", &e.channel.id);
+            if let Some(name) = &e.channel.name {
+                obj.insert("name".into(), AgentValue::string(name.clone()));
+            }
+            if let Some(creator) = &e.channel.creator {
+                obj.insert("user".into(), AgentValue::string(creator.to_string()));
+            }
+            obj.insert(
+                "created".into(),
+                AgentValue::integer(e.channel.created.0.as_second()),
+            );
+            Some((Some(e.channel.id.clone()), AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::ChannelRename(e) => {
+            let mut obj = channel_event_object("channel_rename", &e.channel.id);
+            if let Some(name) = &e.channel.name {
+                obj.insert("name".into(), AgentValue::string(name.clone()));
+            }
+            Some((Some(e.channel.id.clone()), AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::ChannelArchive(e) => {
+            let mut obj = channel_event_object("channel_archive", &e.channel);
+            obj.insert("user".into(), AgentValue::string(e.user.to_string()));
+            Some((Some(e.channel.clone()), AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::ChannelUnarchive(e) => {
+            let mut obj = channel_event_object("channel_unarchive", &e.channel);
+            obj.insert("user".into(), AgentValue::string(e.user.to_string()));
+            Some((Some(e.channel.clone()), AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::FileShared(e) => {
+            let obj = hashmap! {
+                "type".into() => AgentValue::string("file_shared"),
+                "channel".into() => AgentValue::string(e.channel_id.to_string()),
+                "file_id".into() => AgentValue::string(e.file_id.to_string()),
+                "user".into() => AgentValue::string(e.user_id.to_string()),
+                "event_ts".into() => AgentValue::string(e.event_ts.to_string()),
+            };
+            Some((Some(e.channel_id.clone()), AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::ChannelDeleted(e) => {
+            let obj = channel_event_object("channel_deleted", &e.channel);
+            Some((Some(e.channel.clone()), AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::LinkShared(e) => {
+            let links = e
+                .links
+                .iter()
+                .map(|link| {
+                    AgentValue::object(hashmap! {
+                        "url".into() => AgentValue::string(link.url.to_string()),
+                        "domain".into() => AgentValue::string(link.domain.clone()),
+                    })
+                })
+                .collect();
+            let mut obj = hashmap! {
+                "type".into() => AgentValue::string("link_shared"),
+                "channel".into() => AgentValue::string(e.channel.to_string()),
+                "ts".into() => AgentValue::string(e.message_ts.to_string()),
+                "user".into() => AgentValue::string(e.user.to_string()),
+                "links".into() => AgentValue::array(links),
+                "event_ts".into() => AgentValue::string(e.event_ts.to_string()),
+            };
+            if let Some(unfurl_id) = &e.unfurl_id {
+                obj.insert(
+                    "unfurl_id".into(),
+                    AgentValue::string(unfurl_id.to_string()),
+                );
+            }
+            if let Some(source) = &e.source {
+                obj.insert("source".into(), AgentValue::string(source.clone()));
+            }
+            Some((Some(e.channel.clone()), AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::TeamJoin(e) => {
+            let mut obj = hashmap! {
+                "type".into() => AgentValue::string("team_join"),
+                "user".into() => AgentValue::string(e.user.id.to_string()),
+            };
+            if let AgentValue::Object(details) = user_to_value(&e.user) {
+                for (key, detail) in details.iter() {
+                    if key != "id" {
+                        obj.insert(key.clone(), detail.clone());
+                    }
+                }
+            }
+            Some((None, AgentValue::object(obj)))
+        }
+        SlackEventCallbackBody::Unknown(json) => pin_event_to_value(json),
+        _ => None,
+    }
+}
+
+/// slack-morphism does not model `pin_added` / `pin_removed`, so they arrive as raw
+/// JSON and are read field by field.
+fn pin_event_to_value(json: &serde_json::Value) -> Option<(Option<SlackChannelId>, AgentValue)> {
+    let event_type = json.get("type")?.as_str()?;
+    if event_type != "pin_added" && event_type != "pin_removed" {
+        return None;
+    }
+    let str_field = |v: &serde_json::Value, key: &str| {
+        v.get(key).and_then(|f| f.as_str()).map(|f| f.to_string())
+    };
+
+    let item = json.get("item");
+    let channel = str_field(json, "channel_id")
+        .or_else(|| item.and_then(|item| str_field(item, "channel")))
+        .map(SlackChannelId::new);
+    let mut obj = hashmap! {
+        "type".into() => AgentValue::string(event_type),
+    };
+    if let Some(channel) = &channel {
+        obj.insert("channel".into(), AgentValue::string(channel.to_string()));
+    }
+    for key in ["user", "event_ts"] {
+        if let Some(value) = str_field(json, key) {
+            obj.insert(key.into(), AgentValue::string(value));
+        }
+    }
+    if let Some(message) = item.and_then(|item| item.get("message")) {
+        if let Some(ts) = str_field(message, "ts") {
+            obj.insert("ts".into(), AgentValue::string(ts));
+        }
+        if let Some(text) = str_field(message, "text") {
+            obj.insert("text".into(), AgentValue::string(text));
+        }
+        if let Some(author) = str_field(message, "user") {
+            obj.insert("item_user".into(), AgentValue::string(author));
+        }
+    }
+    if let Some(file_id) = item
+        .and_then(|item| item.get("file"))
+        .and_then(|file| str_field(file, "id"))
+    {
+        obj.insert("file_id".into(), AgentValue::string(file_id));
+    }
+    Some((channel, AgentValue::object(obj)))
+}
+
+fn channel_event_object(
+    event_type: &str,
+    channel: &SlackChannelId,
+) -> im::HashMap<String, AgentValue> {
+    hashmap! {
+        "type".into() => AgentValue::string(event_type),
+        "channel".into() => AgentValue::string(channel.to_string()),
+    }
+}
+
+fn reaction_event_to_value(
+    event_type: &str,
+    reaction: &SlackReactionName,
+    user: &SlackUserId,
+    item_user: Option<&SlackUserId>,
+    item: &SlackReactionsItem,
+    event_ts: &SlackTs,
+) -> (Option<SlackChannelId>, AgentValue) {
+    let mut obj = hashmap! {
+        "type".into() => AgentValue::string(event_type),
+        "reaction".into() => AgentValue::string(reaction.to_string()),
+        "user".into() => AgentValue::string(user.to_string()),
+        "event_ts".into() => AgentValue::string(event_ts.to_string()),
+    };
+    if let Some(item_user) = item_user {
+        obj.insert(
+            "item_user".into(),
+            AgentValue::string(item_user.to_string()),
+        );
+    }
+    let channel = match item {
+        SlackReactionsItem::Message(msg) => {
+            obj.insert("ts".into(), AgentValue::string(msg.origin.ts.to_string()));
+            msg.origin.channel.clone()
+        }
+        SlackReactionsItem::File(file) => {
+            obj.insert("file_id".into(), AgentValue::string(file.id.to_string()));
+            None
+        }
+    };
+    if let Some(channel) = &channel {
+        obj.insert("channel".into(), AgentValue::string(channel.to_string()));
+    }
+    (channel, AgentValue::object(obj))
+}
+
+/// Whether events in `channel` pass the Listener's channel filter. Events without a
+/// channel always pass.
+fn listener_channel_allowed(
+    state: &SlackListenerUserState,
+    channel: Option<&SlackChannelId>,
+) -> bool {
+    let (Some(filter), Some(channel)) = (&state.channel_filter, channel) else {
+        return true;
+    };
+    filter.contains(channel)
+}
+
+/// Whether messages and events by `user` pass the Listener's `allowed_users` and
+/// `blocked_users`. Without a user they pass unless an allowlist is set.
+fn listener_user_allowed(state: &SlackListenerUserState, user: Option<&SlackUserId>) -> bool {
+    match user {
+        Some(user) => {
+            !state.blocked_users.contains(user)
+                && state
+                    .allowed_users
+                    .as_ref()
+                    .is_none_or(|allowed| allowed.contains(user))
+        }
+        None => state.allowed_users.is_none(),
+    }
 }
 
-#[async_trait]
-impl AsAgent for SlackListenerAgent {
-    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
-        Ok(Self {
-            data: AgentData::new(ma, id, spec),
-            shutdown_tx: None,
-        })
+/// Reads comma-separated user IDs or email addresses from the `key` config,
+/// resolving emails to IDs.
+async fn config_user_ids(
+    session: &slack::Session<'_>,
+    config: &AgentConfigs,
+    key: &str,
+) -> Result<HashSet<SlackUserId>, AgentError> {
+    let mut ids = HashSet::new();
+    for user in user_list(Some(&AgentValue::string(config.get_string_or_default(key)))) {
+        ids.insert(slack::resolve_user_id(session, &user).await?);
     }
+    Ok(ids)
+}
 
-    async fn start(&mut self) -> Result<(), AgentError> {
-        let client = Arc::new(get_client().clone());
+/// Which messages the Listener forwards by their place in a thread.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ThreadMode {
+    All,
+    /// Only replies in threads.
+    ThreadsOnly,
+    /// Only messages posted to the channel itself.
+    TopLevelOnly,
+}
 
-        let bot_token = get_token(self.ma())?;
-        let bot_session = client.open_session(&bot_token);
-        let bot_user_id = bot_session
-            .auth_test()
-            .await
-            .map_err(|e| AgentError::IoError(format!("Slack API error during auth_test: {}", e)))?
-            .user_id;
+impl ThreadMode {
+    /// Reads the `thread_mode` config.
+    fn from_config(config: &AgentConfigs) -> Result<Self, AgentError> {
+        let mode = config.get_string_or_default(CONFIG_THREAD_MODE);
+        match mode.trim().to_lowercase().as_str() {
+            "" | "all" => Ok(Self::All),
+            "threads_only" => Ok(Self::ThreadsOnly),
+            "top_level_only" => Ok(Self::TopLevelOnly),
+            other => Err(AgentError::InvalidConfig(format!(
+                "Unknown thread_mode: {} (expected all, threads_only or top_level_only)",
+                other
+            ))),
+        }
+    }
 
-        let config = self.configs()?;
-        let channel_filter = config.get_string_or_default(CONFIG_CHANNEL);
-        let channel_filter = if channel_filter.is_empty() {
-            None
-        } else {
-            Some(channel_filter)
-        };
+    fn accepts(self, msg: &SlackMessageEvent) -> bool {
+        // A thread's parent carries its own ts as thread_ts
+        let is_reply = msg
+            .origin
+            .thread_ts
+            .as_ref()
+            .is_some_and(|thread_ts| *thread_ts != msg.origin.ts);
+        match self {
+            Self::All => true,
+            Self::ThreadsOnly => is_reply,
+            Self::TopLevelOnly => !is_reply,
+        }
+    }
+}
 
-        let app_token = get_app_token(self.ma())?;
+/// Whether the Listener's `trigger` lets `msg` through.
+///
+/// In the mention modes, a mention delivered both as a message and as an
+/// `app_mention` event is forwarded only once. In `all` mode `app_mention` events
+/// are dropped, as the message event already carries them.
+fn listener_triggered(
+    state: &SlackListenerUserState,
+    msg: &SlackMessageEvent,
+    is_app_mention: bool,
+) -> bool {
+    let mentioned = is_app_mention
+        || msg
+            .content
+            .as_ref()
+            .and_then(|c| c.text.as_deref())
+            .is_some_and(|text| text.contains(&format!("<@{}>", state.bot_user_id)));
+    // Fall back to the `D` prefix of DM channel IDs when the type is missing
+    let is_dm = match &msg.origin.channel_type {
+        Some(channel_type) => channel_type.0 == "im",
+        None => msg
+            .origin
+            .channel
+            .as_ref()
+            .is_some_and(|c| c.0.starts_with('D')),
+    };
+    match state.trigger {
+        ListenerTrigger::All => !is_app_mention,
+        ListenerTrigger::Dm => is_dm && !is_app_mention,
+        ListenerTrigger::DmOrMention if is_dm && !mentioned => true,
+        ListenerTrigger::Mention | ListenerTrigger::DmOrMention => {
+            if !mentioned {
+                return false;
+            }
+            let mut recent = state
+                .recent_mentions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if recent.contains(&msg.origin.ts) {
+                return false;
+            }
+            if recent.len() == RECENT_MENTIONS {
+                recent.pop_front();
+            }
+            recent.push_back(msg.origin.ts.clone());
+            true
+        }
+    }
+}
+
+async fn interaction_events_handler(
+    event: SlackInteractionEvent,
+    _client: Arc<SlackHyperClient>,
+    states: SlackClientEventsUserState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let values = interaction_to_values(&event);
+    if values.is_empty() {
+        return Ok(());
+    }
+    let Some(connection) = socket_connection(&states).await else {
+        return Ok(());
+    };
 
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
-        self.shutdown_tx = Some(shutdown_tx);
+    for state in connection.listeners() {
+        for (channel, value) in &values {
+            let user = value.get_str("user").map(SlackUserId::from);
+            if listener_channel_allowed(&state, channel.as_ref())
+                && listener_user_allowed(&state, user.as_ref())
+            {
+                state.output(PORT_INTERACTION, value.clone());
+            }
+        }
+    }
 
-        let ma = self.ma().clone();
-        let id = self.id().to_string();
-        let bot_token_str = bot_token.token_value.0.clone();
+    Ok(())
+}
 
-        tokio::spawn(async move {
-            let user_state = SlackListenerUserState {
-                ma,
-                id,
-                channel_filter,
-                bot_user_id,
-                bot_token: bot_token_str,
+/// Converts an interaction payload to the values output on the `interaction` port,
+/// each with the channel it happened in, if any.
+fn interaction_to_values(
+    event: &SlackInteractionEvent,
+) -> Vec<(Option<SlackChannelId>, AgentValue)> {
+    match event {
+        SlackInteractionEvent::BlockActions(e) => block_actions_to_values(e),
+        SlackInteractionEvent::Shortcut(e) => {
+            let obj = hashmap! {
+                "type".into() => AgentValue::string("shortcut"),
+                "callback_id".into() => AgentValue::string(e.callback_id.to_string()),
+                "user".into() => AgentValue::string(e.user.id.to_string()),
+                "trigger_id".into() => AgentValue::string(e.trigger_id.to_string()),
             };
+            vec![(None, AgentValue::object(obj))]
+        }
+        SlackInteractionEvent::MessageAction(e) => vec![message_action_to_value(e)],
+        SlackInteractionEvent::ViewSubmission(e) => {
+            vec![view_to_value("view_submission", &e.user, &e.view)]
+        }
+        SlackInteractionEvent::ViewClosed(e) => {
+            vec![view_to_value("view_closed", &e.user, &e.view)]
+        }
+        _ => Vec::new(),
+    }
+}
 
-            let listener_environment = Arc::new(
-                SlackClientEventsListenerEnvironment::new(client.clone())
-                    .with_user_state(user_state),
-            );
+/// One value per action, since a single `block_actions` payload may carry several.
+fn block_actions_to_values(
+    event: &SlackInteractionBlockActionsEvent,
+) -> Vec<(Option<SlackChannelId>, AgentValue)> {
+    let (container_channel, message_ts) = match &event.container {
+        SlackInteractionActionContainer::Message(c) => (c.channel_id.clone(), Some(&c.message_ts)),
+        SlackInteractionActionContainer::MessageAttachment(c) => {
+            (c.channel_id.clone(), Some(&c.message_ts))
+        }
+        SlackInteractionActionContainer::View(_) => (None, None),
+    };
+    let channel = event
+        .channel
+        .as_ref()
+        .map(|c| c.id.clone())
+        .or(container_channel);
 
-            let socket_mode_callbacks =
-                SlackSocketModeListenerCallbacks::new().with_push_events(push_events_handler);
+    let mut base = hashmap! {
+        "type".into() => AgentValue::string("block_actions"),
+        "trigger_id".into() => AgentValue::string(event.trigger_id.to_string()),
+    };
+    if let Some(user) = &event.user {
+        base.insert("user".into(), AgentValue::string(user.id.to_string()));
+    }
+    if let Some(channel) = &channel {
+        base.insert("channel".into(), AgentValue::string(channel.to_string()));
+    }
+    if let Some(ts) = message_ts {
+        base.insert("ts".into(), AgentValue::string(ts.to_string()));
+    }
+    if let Some(thread_ts) = event
+        .message
+        .as_ref()
+        .and_then(|m| m.origin.thread_ts.as_ref())
+    {
+        base.insert(
+            "thread_ts".into(),
+            AgentValue::string(thread_ts.to_string()),
+        );
+    }
+    if let Some(response_url) = &event.response_url {
+        base.insert(
+            "response_url".into(),
+            AgentValue::string(response_url.0.to_string()),
+        );
+    }
 
-            let socket_mode_listener = SlackClientSocketModeListener::new(
-                &SlackClientSocketModeConfig::new(),
-                listener_environment,
-                socket_mode_callbacks,
+    event
+        .actions
+        .iter()
+        .flatten()
+        .map(|action| {
+            let mut obj = base.clone();
+            obj.insert(
+                "action_id".into(),
+                AgentValue::string(action.action_id.to_string()),
             );
-
-            if let Err(e) = socket_mode_listener.listen_for(&app_token).await {
-                error!("Socket mode listener failed to start: {}", e);
-                return;
+            obj.insert(
+                "action_type".into(),
+                AgentValue::string(action.action_type.to_string()),
+            );
+            if let Some(block_id) = &action.block_id {
+                obj.insert("block_id".into(), AgentValue::string(block_id.to_string()));
             }
+            if let Some(value) = action_value(action) {
+                obj.insert("value".into(), value);
+            }
+            (channel.clone(), AgentValue::object(obj))
+        })
+        .collect()
+}
 
-            socket_mode_listener.start().await;
-
-            // Wait for shutdown signal instead of using serve() which sets its own Ctrl-C handler
-            shutdown_rx.recv().await;
-
-            socket_mode_listener.shutdown().await;
-        });
-
-        Ok(())
+/// A message shortcut, with the message it was invoked on.
+fn message_action_to_value(
+    event: &SlackInteractionMessageActionEvent,
+) -> (Option<SlackChannelId>, AgentValue) {
+    let channel = event.channel.as_ref().map(|c| c.id.clone());
+    let mut obj = hashmap! {
+        "type".into() => AgentValue::string("message_action"),
+        "callback_id".into() => AgentValue::string(event.callback_id.to_string()),
+        "user".into() => AgentValue::string(event.user.id.to_string()),
+        "trigger_id".into() => AgentValue::string(event.trigger_id.to_string()),
+        "response_url".into() => AgentValue::string(event.response_url.0.to_string()),
+    };
+    if let Some(channel) = &channel {
+        obj.insert("channel".into(), AgentValue::string(channel.to_string()));
     }
-
-    async fn stop(&mut self) -> Result<(), AgentError> {
-        if let Some(tx) = self.shutdown_tx.take() {
-            let _ = tx.send(()).await;
-        }
-        Ok(())
+    if let Some(message) = &event.message {
+        obj.insert(
+            "ts".into(),
+            AgentValue::string(message.origin.ts.to_string()),
+        );
+        obj.insert("message".into(), slack_message_to_agent_value(message));
     }
+    (channel, AgentValue::object(obj))
 }
 
-async fn push_events_handler(
-    event: SlackPushEventCallback,
-    _client: Arc<SlackHyperClient>,
-    states: SlackClientEventsUserState,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    if let SlackEventCallbackBody::Message(msg_event) = event.event {
-        let storage = states.read().await;
-        let Some(state) = storage.get_user_state::<SlackListenerUserState>() else {
-            error!("SlackListenerUserState not found in storage");
-            return Ok(());
-        };
+/// A submitted or closed modal, with the inputs' current values flattened from
+/// `state.values` into one object keyed by `action_id`.
+fn view_to_value(
+    event_type: &str,
+    user: &SlackBasicUserInfo,
+    view: &SlackStatefulView,
+) -> (Option<SlackChannelId>, AgentValue) {
+    let mut obj = hashmap! {
+        "type".into() => AgentValue::string(event_type),
+        "user".into() => AgentValue::string(user.id.to_string()),
+        "view_id".into() => AgentValue::string(view.state_params.id.to_string()),
+    };
+    if let SlackView::Modal(modal) = &view.view {
+        if let Some(callback_id) = &modal.callback_id {
+            obj.insert(
+                "callback_id".into(),
+                AgentValue::string(callback_id.to_string()),
+            );
+        }
+        if let Some(private_metadata) = &modal.private_metadata {
+            obj.insert(
+                "private_metadata".into(),
+                AgentValue::string(private_metadata.clone()),
+            );
+        }
+    }
 
-        // Apply channel filter if configured
-        if let Some(ref filter) = state.channel_filter {
-            if let Some(ref channel) = msg_event.origin.channel {
-                let channel_str = channel.to_string();
-                if channel_str != *filter && !filter.ends_with(&channel_str) {
-                    return Ok(());
+    let mut values = im::HashMap::new();
+    if let Some(state) = &view.state_params.state {
+        for actions in state.values.values() {
+            for (action_id, state_value) in actions {
+                if let Some(value) = view_state_value(state_value) {
+                    values.insert(action_id.to_string(), value);
                 }
             }
         }
+    }
+    obj.insert("values".into(), AgentValue::object(values));
 
-        // Clone necessary data before releasing the lock
-        let bot_user_id = state.bot_user_id.clone();
-        let bot_token = state.bot_token.clone();
-        let ma = state.ma.clone();
-        let id = state.id.clone();
-
-        // Check if bot's own message
-        if let Some(ref user) = msg_event.sender.user {
-            if user == &bot_user_id {
-                return Ok(());
-            }
-        }
+    (None, AgentValue::object(obj))
+}
 
-        // Download image if present
-        #[cfg(feature = "image")]
-        let image = download_first_image(&msg_event, &bot_token).await;
-        #[cfg(not(feature = "image"))]
-        let image: Option<PhotonImage> = None;
+/// The current value of a modal input, whatever kind of element it is. Inputs the
+/// user left empty have none.
+fn view_state_value(state: &SlackViewStateValue) -> Option<AgentValue> {
+    let strings =
+        |items: Vec<String>| AgentValue::array(items.into_iter().map(AgentValue::string).collect());
+    if let Some(value) = &state.value {
+        return Some(AgentValue::string(value.clone()));
+    }
+    if let Some(option) = &state.selected_option {
+        return Some(AgentValue::string(option.value.clone()));
+    }
+    if let Some(options) = &state.selected_options {
+        return Some(strings(options.iter().map(|o| o.value.clone()).collect()));
+    }
+    if let Some(users) = &state.selected_users {
+        return Some(strings(users.iter().map(|u| u.to_string()).collect()));
+    }
+    if let Some(conversations) = &state.selected_conversations {
+        return Some(strings(
+            conversations.iter().map(|c| c.to_string()).collect(),
+        ));
+    }
+    if let Some(date_time) = &state.selected_date_time {
+        return Some(AgentValue::integer(date_time.0.as_second()));
+    }
+    [
+        state.selected_date.clone(),
+        state.selected_time.clone(),
+        state.selected_user.as_ref().map(|u| u.to_string()),
+        state.selected_channel.as_ref().map(|c| c.to_string()),
+        state.selected_conversation.as_ref().map(|c| c.to_string()),
+    ]
+    .into_iter()
+    .flatten()
+    .next()
+    .map(AgentValue::string)
+}
 
-        if let Some(message) = slack_push_message_to_agent_value(&msg_event, image) {
-            if let Err(e) = ma.try_send_agent_out(
-                id,
-                AgentContext::new(),
-                PORT_VALUE.to_string(),
-                message,
-            ) {
-                error!("Failed to output message: {}", e);
-            }
-        }
+/// The value a user picked: the button's `value`, the selected option's value, or
+/// an array of values for multi-selects.
+fn action_value(action: &SlackInteractionActionInfo) -> Option<AgentValue> {
+    if let Some(value) = &action.value {
+        return Some(AgentValue::string(value.clone()));
+    }
+    if let Some(option) = &action.selected_option {
+        return Some(AgentValue::string(option.value.clone()));
     }
+    action.selected_options.as_ref().map(|options| {
+        AgentValue::array(
+            options
+                .iter()
+                .map(|o| AgentValue::string(o.value.clone()))
+                .collect(),
+        )
+    })
+}
 
-    Ok(())
+/// Converts an `app_mention` event to the message event the Listener outputs.
+fn app_mention_to_message(mention: SlackAppMentionEvent) -> SlackMessageEvent {
+    let mut origin = mention.origin;
+    origin.channel = Some(mention.channel);
+    SlackMessageEvent::new(origin, SlackMessageSender::new().with_user(mention.user))
+        .with_content(mention.content)
 }
 
 #[cfg(feature = "image")]
@@ -677,9 +6629,12 @@ async fn download_first_image(msg: &SlackMessageEvent, bot_token: &str) -> Optio
             continue;
         }
 
-        let url = file.url_private_download.as_ref().or(file.url_private.as_ref())?;
+        let url = file
+            .url_private_download
+            .as_ref()
+            .or(file.url_private.as_ref())?;
 
-        match download_slack_file(url.as_str(), bot_token).await {
+        match slack::download_file(url.as_str(), bot_token).await {
             Ok(bytes) => {
                 let image = PhotonImage::new_from_byteslice(bytes);
                 return Some(image);
@@ -694,30 +6649,6 @@ async fn download_first_image(msg: &SlackMessageEvent, bot_token: &str) -> Optio
     None
 }
 
-#[cfg(feature = "image")]
-async fn download_slack_file(url: &str, bot_token: &str) -> Result<Vec<u8>, AgentError> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .header("Authorization", format!("Bearer {}", bot_token))
-        .send()
-        .await
-        .map_err(|e| AgentError::IoError(format!("Failed to fetch file: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Err(AgentError::IoError(format!(
-            "Failed to download file: HTTP {}",
-            response.status()
-        )));
-    }
-
-    response
-        .bytes()
-        .await
-        .map(|b| b.to_vec())
-        .map_err(|e| AgentError::IoError(format!("Failed to read file bytes: {}", e)))
-}
-
 fn slack_push_message_to_agent_value(
     msg: &SlackMessageEvent,
     #[allow(unused_variables)] image: Option<PhotonImage>,
@@ -741,40 +6672,33 @@ fn slack_push_message_to_agent_value(
 
     let user = msg.sender.user.as_ref().map(|u| u.to_string());
 
+    let metadata = msg
+        .content
+        .as_ref()
+        .and_then(|c| c.metadata.as_ref())
+        .map(metadata_to_value);
+
+    #[cfg_attr(not(feature = "image"), allow(unused_mut))]
+    let mut message = Message::user(text);
     #[cfg(feature = "image")]
     {
-        let mut message = Message::user(text);
         message.image = image.map(Arc::new);
-
-        let mut obj = im::HashMap::new();
-        obj.insert("message".into(), AgentValue::message(message));
-        if let Some(user) = user {
-            obj.insert("user".into(), AgentValue::string(user));
-        }
-        obj.insert("channel".into(), AgentValue::string(channel));
-        obj.insert("ts".into(), AgentValue::string(ts));
-        if let Some(thread_ts) = thread_ts {
-            obj.insert("thread_ts".into(), AgentValue::string(thread_ts));
-        }
-        Some(AgentValue::object(obj))
     }
 
-    #[cfg(not(feature = "image"))]
-    {
-        let message = Message::user(text);
-
-        let mut obj = im::HashMap::new();
-        obj.insert("message".into(), AgentValue::message(message));
-        if let Some(user) = user {
-            obj.insert("user".into(), AgentValue::string(user));
-        }
-        obj.insert("channel".into(), AgentValue::string(channel));
-        obj.insert("ts".into(), AgentValue::string(ts));
-        if let Some(thread_ts) = thread_ts {
-            obj.insert("thread_ts".into(), AgentValue::string(thread_ts));
-        }
-        Some(AgentValue::object(obj))
+    let mut obj = im::HashMap::new();
+    obj.insert("message".into(), AgentValue::message(message));
+    if let Some(user) = user {
+        obj.insert("user".into(), AgentValue::string(user));
+    }
+    obj.insert("channel".into(), AgentValue::string(channel));
+    obj.insert("ts".into(), AgentValue::string(ts));
+    if let Some(thread_ts) = thread_ts {
+        obj.insert("thread_ts".into(), AgentValue::string(thread_ts));
     }
+    if let Some(metadata) = metadata {
+        obj.insert("metadata".into(), metadata);
+    }
+    Some(AgentValue::object(obj))
 }
 
 /// Agent for converting Slack messages to LLM Message format.
@@ -828,6 +6752,161 @@ impl AsAgent for SlackToMessageAgent {
     }
 }
 
+/// Agent for turning a fetched channel history into an LLM conversation.
+///
+/// Orders the messages chronologically and gives each one a role: messages from the
+/// bot user become `assistant` messages, everything else `user` messages.
+///
+/// # Configuration
+/// - `bot_user`: User ID treated as the assistant. Empty looks up the bot token's
+///   user with `auth.test`.
+/// - `include_names`: Prefix user messages with `"[name]: "`, using `user_name` when
+///   present and the user ID otherwise (default: false)
+///
+/// # Input
+/// - `values`: Array of message objects as output by the History agent
+///
+/// # Output
+/// - `messages`: Array of AgentValue::Message, oldest first
+#[modular_agent(
+    title = "ToConversation",
+    category = CATEGORY,
+    inputs = [PORT_VALUES],
+    outputs = [PORT_MESSAGES],
+    string_config(name = CONFIG_BOT_USER),
+    boolean_config(name = CONFIG_INCLUDE_NAMES),
+)]
+struct SlackToConversationAgent {
+    data: AgentData,
+    /// The bot token's user, looked up on first use when `bot_user` is empty.
+    bot_user_id: Option<String>,
+}
+
+#[async_trait]
+impl AsAgent for SlackToConversationAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+            bot_user_id: None,
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let config = self.configs()?;
+        let include_names = config.get_bool_or_default(CONFIG_INCLUDE_NAMES);
+        let bot_user = match non_empty_config(config, CONFIG_BOT_USER) {
+            Some(user) => user,
+            None => self.bot_user_id().await?,
+        };
+
+        let mut history: Vec<&AgentValue> = match value.as_array() {
+            Some(arr) => arr.iter().collect(),
+            None => vec![&value],
+        };
+        history.retain(|msg| msg.get_str("text").is_some_and(|t| !t.is_empty()));
+        history.sort_by_key(|msg| ts_sort_key(msg.get_str("ts").unwrap_or_default()));
+
+        let messages = history
+            .into_iter()
+            .map(|msg| {
+                let text = msg.get_str("text").unwrap_or_default();
+                let user = msg.get_str("user");
+                if user == Some(bot_user.as_str()) {
+                    return AgentValue::message(Message::assistant(text.to_string()));
+                }
+                let name = msg.get_str("user_name").or(user);
+                let text = match name {
+                    Some(name) if include_names => format!("[{}]: {}", name, text),
+                    _ => text.to_string(),
+                };
+                AgentValue::message(Message::user(text))
+            })
+            .collect();
+        self.output(ctx, PORT_MESSAGES, AgentValue::array(messages))
+            .await
+    }
+}
+
+impl SlackToConversationAgent {
+    async fn bot_user_id(&mut self) -> Result<String, AgentError> {
+        if let Some(id) = &self.bot_user_id {
+            return Ok(id.clone());
+        }
+        let token = get_token(self.ma())?;
+        let client = get_client();
+        let session = client.open_session(&token);
+        let id = slack::auth_test(&session).await?.user_id.to_string();
+        self.bot_user_id = Some(id.clone());
+        Ok(id)
+    }
+}
+
+/// Sort key that orders Slack ts values (`seconds.micros`) numerically.
+fn ts_sort_key(ts: &str) -> (u64, u64) {
+    let (secs, micros) = ts.split_once('.').unwrap_or((ts, "0"));
+    (secs.parse().unwrap_or(0), micros.parse().unwrap_or(0))
+}
+
+/// Agent for turning a message permalink into the message's channel and ts.
+///
+/// Lets a link pasted into a flow drive agents that fetch, react to or reply to
+/// that exact message.
+///
+/// # Input
+/// - `value`: Permalink URL, or an object with `permalink`
+///
+/// # Output
+/// - `value`: Object containing `channel`, `ts` and `thread_ts`. For a thread reply,
+///   `thread_ts` is the parent's ts; otherwise it is the message's own ts, so a reply
+///   starts a thread under the linked message.
+#[modular_agent(
+    title = "ParsePermalink",
+    category = CATEGORY,
+    inputs = [PORT_VALUE],
+    outputs = [PORT_VALUE],
+)]
+struct SlackParsePermalinkAgent {
+    data: AgentData,
+}
+
+#[async_trait]
+impl AsAgent for SlackParsePermalinkAgent {
+    fn new(ma: ModularAgent, id: String, spec: AgentSpec) -> Result<Self, AgentError> {
+        Ok(Self {
+            data: AgentData::new(ma, id, spec),
+        })
+    }
+
+    async fn process(
+        &mut self,
+        ctx: AgentContext,
+        _port: String,
+        value: AgentValue,
+    ) -> Result<(), AgentError> {
+        let url = value
+            .as_str()
+            .or_else(|| value.get_str("permalink"))
+            .ok_or_else(|| AgentError::InvalidValue("Permalink is required".to_string()))?;
+        let permalink = slack::parse_permalink(url).ok_or_else(|| {
+            AgentError::InvalidValue(format!("Not a Slack message permalink: {}", url))
+        })?;
+
+        let thread_ts = permalink.thread_ts.unwrap_or_else(|| permalink.ts.clone());
+        let result = hashmap! {
+            "channel".into() => AgentValue::string(permalink.channel_id),
+            "ts".into() => AgentValue::string(permalink.ts),
+            "thread_ts".into() => AgentValue::string(thread_ts),
+        };
+        self.output(ctx, PORT_VALUE, AgentValue::object(result))
+            .await
+    }
+}
+
 fn slack_value_to_message(value: &AgentValue) -> Result<Message, AgentError> {
     match value {
         AgentValue::String(s) => Ok(Message::user(s.to_string())),
@@ -850,3 +6929,209 @@ fn slack_value_to_message(value: &AgentValue) -> Result<Message, AgentError> {
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listener_state(trigger: ListenerTrigger) -> SlackListenerUserState {
+        SlackListenerUserState {
+            ma: ModularAgent::new(),
+            id: "listener".to_string(),
+            channel_filter: None,
+            trigger,
+            thread_mode: ThreadMode::All,
+            allowed_users: None,
+            blocked_users: HashSet::new(),
+            text_filter: None,
+            resolve_mentions: false,
+            bot_user_id: "UBOT".into(),
+            bot_token: String::new(),
+            recent_mentions: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn message_event(json: serde_json::Value) -> SlackMessageEvent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_listener_triggered_mention_forwarded_once() {
+        let state = listener_state(ListenerTrigger::Mention);
+        let msg = message_event(serde_json::json!({
+            "channel": "C0123",
+            "channel_type": "channel",
+            "user": "U0123",
+            "text": "<@UBOT> deploy please",
+            "ts": "1700000000.000100",
+        }));
+        assert!(listener_triggered(&state, &msg, false));
+        assert!(!listener_triggered(&state, &msg, true));
+
+        let other = message_event(serde_json::json!({
+            "channel": "C0123",
+            "user": "U0123",
+            "text": "no mention here",
+            "ts": "1700000000.000200",
+        }));
+        assert!(!listener_triggered(&state, &other, false));
+    }
+
+    #[test]
+    fn test_listener_triggered_all_drops_app_mention() {
+        let state = listener_state(ListenerTrigger::All);
+        let msg = message_event(serde_json::json!({
+            "channel": "C0123",
+            "user": "U0123",
+            "text": "<@UBOT> hello",
+            "ts": "1700000000.000100",
+        }));
+        assert!(listener_triggered(&state, &msg, false));
+        assert!(!listener_triggered(&state, &msg, true));
+    }
+
+    #[test]
+    fn test_listener_triggered_dm_or_mention() {
+        let state = listener_state(ListenerTrigger::DmOrMention);
+        // No channel_type, so the D prefix marks the DM
+        let dm = message_event(serde_json::json!({
+            "channel": "D0123",
+            "user": "U0123",
+            "text": "hello",
+            "ts": "1700000000.000100",
+        }));
+        assert!(listener_triggered(&state, &dm, false));
+
+        let im = message_event(serde_json::json!({
+            "channel": "C0456",
+            "channel_type": "im",
+            "user": "U0123",
+            "text": "hello",
+            "ts": "1700000000.000200",
+        }));
+        assert!(listener_triggered(&state, &im, false));
+
+        let channel = message_event(serde_json::json!({
+            "channel": "C0123",
+            "channel_type": "channel",
+            "user": "U0123",
+            "text": "hello",
+            "ts": "1700000000.000300",
+        }));
+        assert!(!listener_triggered(&state, &channel, false));
+    }
+
+    #[test]
+    fn test_thread_mode_accepts() {
+        let top_level = message_event(serde_json::json!({
+            "channel": "C0123",
+            "text": "parent",
+            "ts": "1700000000.000100",
+            "thread_ts": "1700000000.000100",
+        }));
+        let reply = message_event(serde_json::json!({
+            "channel": "C0123",
+            "text": "reply",
+            "ts": "1700000000.000200",
+            "thread_ts": "1700000000.000100",
+        }));
+        assert!(ThreadMode::All.accepts(&top_level));
+        assert!(ThreadMode::All.accepts(&reply));
+        assert!(!ThreadMode::ThreadsOnly.accepts(&top_level));
+        assert!(ThreadMode::ThreadsOnly.accepts(&reply));
+        assert!(ThreadMode::TopLevelOnly.accepts(&top_level));
+        assert!(!ThreadMode::TopLevelOnly.accepts(&reply));
+    }
+
+    #[test]
+    fn test_listener_user_allowed() {
+        let alice: SlackUserId = "UALICE".into();
+        let bob: SlackUserId = "UBOB".into();
+
+        let mut state = listener_state(ListenerTrigger::All);
+        state.blocked_users.insert(bob.clone());
+        assert!(listener_user_allowed(&state, Some(&alice)));
+        assert!(!listener_user_allowed(&state, Some(&bob)));
+        assert!(listener_user_allowed(&state, None));
+
+        state.allowed_users = Some(HashSet::from([alice.clone()]));
+        assert!(listener_user_allowed(&state, Some(&alice)));
+        assert!(!listener_user_allowed(&state, Some(&"UCAROL".into())));
+        assert!(!listener_user_allowed(&state, None));
+    }
+
+    #[test]
+    fn test_pin_event_to_value_message() {
+        let (channel, value) = pin_event_to_value(&serde_json::json!({
+            "type": "pin_added",
+            "user": "U0PINNER",
+            "channel_id": "C0123",
+            "item": {
+                "type": "message",
+                "channel": "C0123",
+                "created_by": "U0PINNER",
+                "message": {
+                    "type": "message",
+                    "user": "U0AUTHOR",
+                    "text": "Deploy at 5pm",
+                    "ts": "1700000000.000100",
+                },
+            },
+            "event_ts": "1700000100.000200",
+        }))
+        .unwrap();
+        assert_eq!(channel, Some("C0123".into()));
+        assert_eq!(value.get_str("type"), Some("pin_added"));
+        assert_eq!(value.get_str("channel"), Some("C0123"));
+        assert_eq!(value.get_str("user"), Some("U0PINNER"));
+        assert_eq!(value.get_str("event_ts"), Some("1700000100.000200"));
+        assert_eq!(value.get_str("ts"), Some("1700000000.000100"));
+        assert_eq!(value.get_str("text"), Some("Deploy at 5pm"));
+        assert_eq!(value.get_str("item_user"), Some("U0AUTHOR"));
+        assert_eq!(value.get_str("file_id"), None);
+    }
+
+    #[test]
+    fn test_pin_event_to_value_file() {
+        // Without channel_id the channel is read from the item
+        let (channel, value) = pin_event_to_value(&serde_json::json!({
+            "type": "pin_added",
+            "user": "U0PINNER",
+            "item": {
+                "type": "file",
+                "channel": "C0456",
+                "file": {"id": "F0789", "name": "report.csv"},
+            },
+            "event_ts": "1700000100.000200",
+        }))
+        .unwrap();
+        assert_eq!(channel, Some("C0456".into()));
+        assert_eq!(value.get_str("channel"), Some("C0456"));
+        assert_eq!(value.get_str("file_id"), Some("F0789"));
+        assert_eq!(value.get_str("ts"), None);
+        assert_eq!(value.get_str("item_user"), None);
+    }
+
+    #[test]
+    fn test_pin_event_to_value_removed() {
+        let (channel, value) = pin_event_to_value(&serde_json::json!({
+            "type": "pin_removed",
+            "user": "U0PINNER",
+            "channel_id": "C0123",
+            "item": {
+                "type": "message",
+                "channel": "C0123",
+                "message": {"user": "U0AUTHOR", "text": "old", "ts": "1700000000.000100"},
+            },
+            "has_pins": false,
+            "event_ts": "1700000200.000300",
+        }))
+        .unwrap();
+        assert_eq!(channel, Some("C0123".into()));
+        assert_eq!(value.get_str("type"), Some("pin_removed"));
+        assert_eq!(value.get_str("ts"), Some("1700000000.000100"));
+        assert_eq!(value.get_str("item_user"), Some("U0AUTHOR"));
+
+        assert!(pin_event_to_value(&serde_json::json!({"type": "star_added"})).is_none());
+    }
+}
@@ -0,0 +1,811 @@
+use std::sync::LazyLock;
+
+use modular_agent_core::AgentError;
+use regex::Regex;
+use serde_json::Value;
+use slack_morphism::prelude::*;
+
+use crate::mrkdwn::{self, MrkdwnOptions};
+
+/// Maximum number of blocks Slack accepts in a single message.
+pub const MAX_BLOCKS: usize = 50;
+
+/// Maximum text length of a section block.
+pub const MAX_SECTION_TEXT_LENGTH: usize = 3000;
+
+/// Maximum text length of a header block.
+pub const MAX_HEADER_TEXT_LENGTH: usize = 150;
+
+static HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^#{1,6}\s+(.+?)\s*#*\s*$").unwrap());
+static HR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[-*_]{3,}\s*$").unwrap());
+static FENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*```\s*([\w+#.-]*)").unwrap());
+
+/// Convert Markdown text to Block Kit blocks.
+///
+/// Headings become `header` blocks, horizontal rules become `divider` blocks and
+/// fenced code becomes a preformatted `rich_text` block. Everything else is
+/// converted with [`mrkdwn::md_to_mrkdwn_with_options`] into `section` blocks,
+/// split so that no section exceeds [`MAX_SECTION_TEXT_LENGTH`].
+///
+/// The result may hold more than [`MAX_BLOCKS`] blocks; callers posting it must
+/// spread it over several messages.
+pub fn md_to_blocks(input: &str, options: &MrkdwnOptions) -> Vec<SlackBlock> {
+    let input = input.replace("\r\n", "\n");
+
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<(Option<String>, Vec<&str>)> = None;
+
+    for line in input.split('\n') {
+        if let Some((language, lines)) = code.as_mut() {
+            if line.trim_start().starts_with("```") {
+                blocks.push(code_block(&lines.join("\n"), language.take()));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+
+        if let Some(caps) = FENCE.captures(line) {
+            push_sections(&mut blocks, &mut paragraph, options);
+            let language = Some(caps[1].to_string()).filter(|l| !l.is_empty());
+            code = Some((language, Vec::new()));
+        } else if let Some(caps) = HEADING.captures(line) {
+            push_sections(&mut blocks, &mut paragraph, options);
+            blocks.push(header_block(&caps[1]));
+        } else if HR.is_match(line) {
+            push_sections(&mut blocks, &mut paragraph, options);
+            blocks.push(SlackDividerBlock::new().into());
+        } else {
+            paragraph.push(line);
+        }
+    }
+
+    // An unterminated fence still renders its content as code
+    if let Some((language, lines)) = code {
+        blocks.push(code_block(&lines.join("\n"), language));
+    }
+    push_sections(&mut blocks, &mut paragraph, options);
+    blocks
+}
+
+/// Plain-text summary of blocks, used as the notification fallback `text`.
+///
+/// Truncated to [`mrkdwn::MAX_MESSAGE_LENGTH`] characters.
+pub fn fallback_text(blocks: &[SlackBlock]) -> String {
+    let text = blocks
+        .iter()
+        .filter_map(|block| serde_json::to_value(block).ok())
+        .filter_map(|block| match block["type"].as_str() {
+            Some("header") | Some("section") => block["text"]["text"].as_str().map(String::from),
+            Some("rich_text") => Some(rich_text_content(&block)),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.chars().take(mrkdwn::MAX_MESSAGE_LENGTH).collect()
+}
+
+/// Escapes `@here`/`@channel`/`@everyone` in every `text` of blocks JSON, in place.
+///
+/// Rich text `broadcast` elements are turned into plain text so they no longer
+/// notify anyone.
+pub fn escape_special_mentions(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(escape_special_mentions),
+        Value::Object(obj) if obj.get("type").and_then(Value::as_str) == Some("broadcast") => {
+            let range = obj.get("range").and_then(Value::as_str).unwrap_or("here");
+            *value = serde_json::json!({"type": "text", "text": format!("@\u{200B}{}", range)});
+        }
+        Value::Object(obj) => {
+            for (key, item) in obj.iter_mut() {
+                match item {
+                    Value::String(text) if key == "text" => {
+                        *text = mrkdwn::escape_special_mentions(text);
+                    }
+                    _ => escape_special_mentions(item),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Build blocks from a declarative spec.
+///
+/// The spec is an object with any of the keys below, rendered in this order, or
+/// an array of such objects rendered one after another:
+///
+/// - `header`: Header text
+/// - `section` / `sections`: Section text, or an array of texts
+/// - `fields`: Array of texts shown in two columns within one section
+/// - `image` / `images`: Image URL or `{url, alt_text, title}`, or an array of them
+/// - `buttons`: Array of `{text, url, value, action_id, style}`
+/// - `context`: Context text, or an array of texts
+/// - `divider`: `true` adds a divider
+///
+/// Section, field and context texts are converted with `options` when given.
+pub fn build_blocks(
+    spec: &Value,
+    options: Option<&MrkdwnOptions>,
+) -> Result<Vec<SlackBlock>, AgentError> {
+    let mut blocks = Vec::new();
+    match spec {
+        Value::Array(specs) => {
+            for spec in specs {
+                build_spec_blocks(&mut blocks, spec, options)?;
+            }
+        }
+        _ => build_spec_blocks(&mut blocks, spec, options)?,
+    }
+    Ok(blocks)
+}
+
+const SPEC_KEYS: &[&str] = &[
+    "header", "section", "sections", "fields", "image", "images", "buttons", "context", "divider",
+];
+
+fn build_spec_blocks(
+    blocks: &mut Vec<SlackBlock>,
+    spec: &Value,
+    options: Option<&MrkdwnOptions>,
+) -> Result<(), AgentError> {
+    let Some(obj) = spec.as_object() else {
+        return Err(AgentError::InvalidValue(
+            "Block spec must be an object".to_string(),
+        ));
+    };
+    if let Some(key) = obj.keys().find(|k| !SPEC_KEYS.contains(&k.as_str())) {
+        return Err(AgentError::InvalidValue(format!(
+            "Unknown block spec key: {} (expected one of {})",
+            key,
+            SPEC_KEYS.join(", ")
+        )));
+    }
+    let mrkdwn_text = |text: &str| match options {
+        Some(options) => mrkdwn::md_to_mrkdwn_with_options(text, options),
+        None => text.to_string(),
+    };
+
+    if let Some(header) = obj.get("header") {
+        blocks.push(header_block(spec_str(header, "header")?));
+    }
+    for key in ["section", "sections"] {
+        for text in spec_list(obj.get(key)) {
+            let text = mrkdwn_text(spec_str(text, key)?);
+            blocks.push(
+                SlackSectionBlock::new()
+                    .with_text(SlackBlockMarkDownText::new(text).into())
+                    .into(),
+            );
+        }
+    }
+    if let Some(fields) = obj.get("fields") {
+        let fields = spec_list(Some(fields))
+            .map(|f| Ok(SlackBlockMarkDownText::new(mrkdwn_text(spec_str(f, "fields")?)).into()))
+            .collect::<Result<Vec<_>, AgentError>>()?;
+        blocks.push(SlackSectionBlock::new().with_fields(fields).into());
+    }
+    for key in ["image", "images"] {
+        for image in spec_list(obj.get(key)) {
+            blocks.push(image_block(image)?);
+        }
+    }
+    if let Some(buttons) = obj.get("buttons") {
+        let elements = spec_list(Some(buttons))
+            .map(button_element)
+            .collect::<Result<Vec<_>, AgentError>>()?;
+        blocks.push(SlackActionsBlock::new(elements).into());
+    }
+    if let Some(context) = obj.get("context") {
+        let elements = spec_list(Some(context))
+            .map(|c| Ok(SlackBlockMarkDownText::new(mrkdwn_text(spec_str(c, "context")?)).into()))
+            .collect::<Result<Vec<_>, AgentError>>()?;
+        blocks.push(SlackContextBlock::new(elements).into());
+    }
+    if obj.get("divider").and_then(Value::as_bool) == Some(true) {
+        blocks.push(SlackDividerBlock::new().into());
+    }
+    Ok(())
+}
+
+/// Iterates a single value or the elements of an array.
+fn spec_list(value: Option<&Value>) -> impl Iterator<Item = &Value> {
+    let (single, many) = match value {
+        Some(Value::Array(values)) => (None, values.as_slice()),
+        Some(value) => (Some(value), &[][..]),
+        None => (None, &[][..]),
+    };
+    single.into_iter().chain(many)
+}
+
+fn spec_str<'a>(value: &'a Value, key: &str) -> Result<&'a str, AgentError> {
+    value
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| AgentError::InvalidValue(format!("{} must be a non-empty string", key)))
+}
+
+fn image_block(spec: &Value) -> Result<SlackBlock, AgentError> {
+    let (url, alt_text, title) = match spec {
+        Value::Object(obj) => (
+            obj.get("url").and_then(Value::as_str).unwrap_or_default(),
+            obj.get("alt_text").and_then(Value::as_str),
+            obj.get("title").and_then(Value::as_str),
+        ),
+        _ => (spec.as_str().unwrap_or_default(), None, None),
+    };
+    let image_url = url
+        .parse()
+        .map_err(|_| AgentError::InvalidValue(format!("Invalid image url: {:?}", url)))?;
+    let block = SlackImageBlock::new(
+        SlackImageUrlOrFile::ImageUrl { image_url },
+        // Slack requires alt text; fall back to the URL
+        alt_text.unwrap_or(url).to_string(),
+    )
+    .opt_title(title.map(|t| SlackBlockPlainText::new(t.to_string()).into()));
+    Ok(block.into())
+}
+
+fn button_element(spec: &Value) -> Result<SlackActionBlockElement, AgentError> {
+    let text = spec_str(&spec["text"], "buttons.text")?;
+    let mut button =
+        SlackBlockButtonElement::new(SlackBlockPlainText::new(text.to_string()).into())
+            .opt_action_id(
+                spec["action_id"]
+                    .as_str()
+                    .map(|id| SlackActionId::new(id.to_string())),
+            )
+            .opt_value(spec["value"].as_str().map(String::from));
+    if let Some(url) = spec["url"].as_str() {
+        let url = url
+            .parse()
+            .map_err(|_| AgentError::InvalidValue(format!("Invalid button url: {:?}", url)))?;
+        button = button.with_url(url);
+    }
+    match spec["style"].as_str() {
+        None | Some("") => {}
+        Some("primary") => button = button.with_style(SlackBlockButtonStyle::Primary),
+        Some("danger") => button = button.with_style(SlackBlockButtonStyle::Danger),
+        Some(style) => {
+            return Err(AgentError::InvalidValue(format!(
+                "Invalid button style: {} (expected primary or danger)",
+                style
+            )));
+        }
+    }
+    Ok(button.into())
+}
+
+/// Block types Slack accepts in messages.
+const KNOWN_BLOCK_TYPES: &[&str] = &[
+    "actions",
+    "context",
+    "divider",
+    "file",
+    "header",
+    "image",
+    "input",
+    "markdown",
+    "rich_text",
+    "section",
+    "table",
+    "video",
+];
+
+/// Check blocks against Slack's documented limits before sending.
+///
+/// Catches unknown block types, too many blocks, missing required fields and
+/// over-long texts, so the caller gets an actionable message instead of Slack's
+/// generic `invalid_blocks` error. All problems are reported at once.
+pub fn validate_blocks(blocks: &Value) -> Result<(), AgentError> {
+    let Some(blocks) = blocks.as_array() else {
+        return Err(AgentError::InvalidValue(
+            "Invalid blocks: expected an array of blocks".to_string(),
+        ));
+    };
+
+    let mut problems = Vec::new();
+    if blocks.len() > MAX_BLOCKS {
+        problems.push(format!(
+            "{} blocks given, at most {} are allowed",
+            blocks.len(),
+            MAX_BLOCKS
+        ));
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let block_type = block["type"].as_str().unwrap_or_default();
+        let mut problem =
+            |message: String| problems.push(format!("blocks[{}] ({}): {}", i, block_type, message));
+
+        if block_type.is_empty() {
+            problem("missing type".to_string());
+            continue;
+        }
+        if !KNOWN_BLOCK_TYPES.contains(&block_type) {
+            problem(format!(
+                "unknown block type (expected one of {})",
+                KNOWN_BLOCK_TYPES.join(", ")
+            ));
+            continue;
+        }
+        check_len(&mut problem, "block_id", block["block_id"].as_str(), 255);
+
+        match block_type {
+            "section" => {
+                if block["text"].is_null() && block["fields"].is_null() {
+                    problem("either text or fields is required".to_string());
+                }
+                check_len(&mut problem, "text", block["text"]["text"].as_str(), 3000);
+                if let Some(fields) = block["fields"].as_array() {
+                    if fields.len() > 10 {
+                        problem(format!(
+                            "{} fields given, at most 10 are allowed",
+                            fields.len()
+                        ));
+                    }
+                    for (j, field) in fields.iter().enumerate() {
+                        let name = format!("fields[{}]", j);
+                        check_len(&mut problem, &name, field["text"].as_str(), 2000);
+                    }
+                }
+                check_element(&mut problem, "accessory", &block["accessory"]);
+            }
+            "header" => {
+                if block["text"]["text"].as_str().is_none_or(str::is_empty) {
+                    problem("text is required".to_string());
+                } else if block["text"]["type"] != "plain_text" {
+                    problem("text must be plain_text".to_string());
+                }
+                check_len(
+                    &mut problem,
+                    "text",
+                    block["text"]["text"].as_str(),
+                    MAX_HEADER_TEXT_LENGTH,
+                );
+            }
+            "image" => {
+                if block["image_url"].is_null() && block["slack_file"].is_null() {
+                    problem("image_url or slack_file is required".to_string());
+                }
+                if block["alt_text"].as_str().is_none_or(str::is_empty) {
+                    problem("alt_text is required".to_string());
+                }
+                check_len(&mut problem, "image_url", block["image_url"].as_str(), 3000);
+                check_len(&mut problem, "alt_text", block["alt_text"].as_str(), 2000);
+                check_len(&mut problem, "title", block["title"]["text"].as_str(), 2000);
+            }
+            "actions" | "context" => {
+                let max = if block_type == "actions" { 25 } else { 10 };
+                match block["elements"].as_array() {
+                    Some(elements) if !elements.is_empty() => {
+                        if elements.len() > max {
+                            problem(format!(
+                                "{} elements given, at most {} are allowed",
+                                elements.len(),
+                                max
+                            ));
+                        }
+                        for (j, element) in elements.iter().enumerate() {
+                            check_element(&mut problem, &format!("elements[{}]", j), element);
+                        }
+                    }
+                    _ => problem("elements must be a non-empty array".to_string()),
+                }
+            }
+            "markdown" => {
+                check_len(&mut problem, "text", block["text"].as_str(), 12000);
+            }
+            _ => {}
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(AgentError::InvalidValue(format!(
+            "Invalid blocks: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+fn check_len(problem: &mut impl FnMut(String), name: &str, text: Option<&str>, max: usize) {
+    if let Some(text) = text {
+        let len = text.chars().count();
+        if len > max {
+            problem(format!(
+                "{} is {} characters, at most {} are allowed",
+                name, len, max
+            ));
+        }
+    }
+}
+
+/// Check the limits of an interactive or context element.
+fn check_element(problem: &mut impl FnMut(String), name: &str, element: &Value) {
+    match element["type"].as_str() {
+        Some("button") => {
+            check_len(
+                problem,
+                &format!("{}.text", name),
+                element["text"]["text"].as_str(),
+                75,
+            );
+            check_len(
+                problem,
+                &format!("{}.value", name),
+                element["value"].as_str(),
+                2000,
+            );
+            check_len(
+                problem,
+                &format!("{}.url", name),
+                element["url"].as_str(),
+                3000,
+            );
+            check_len(
+                problem,
+                &format!("{}.action_id", name),
+                element["action_id"].as_str(),
+                255,
+            );
+        }
+        Some("mrkdwn") | Some("plain_text") => {
+            check_len(
+                problem,
+                &format!("{}.text", name),
+                element["text"].as_str(),
+                3000,
+            );
+        }
+        _ => {}
+    }
+}
+
+fn push_sections(blocks: &mut Vec<SlackBlock>, paragraph: &mut Vec<&str>, options: &MrkdwnOptions) {
+    let text = mrkdwn::md_to_mrkdwn_with_options(&paragraph.join("\n"), options);
+    paragraph.clear();
+    if text.trim().is_empty() {
+        return;
+    }
+    for chunk in mrkdwn::split_mrkdwn(&text, MAX_SECTION_TEXT_LENGTH) {
+        blocks.push(
+            SlackSectionBlock::new()
+                .with_text(SlackBlockMarkDownText::new(chunk).into())
+                .into(),
+        );
+    }
+}
+
+fn header_block(text: &str) -> SlackBlock {
+    // Header blocks are plain text, so drop inline Markdown markers
+    let text = text.replace("**", "").replace("__", "").replace('`', "");
+    let text = mrkdwn::truncate(text.trim(), MAX_HEADER_TEXT_LENGTH);
+    SlackHeaderBlock::new(SlackBlockPlainText::new(text).into()).into()
+}
+
+fn code_block(code: &str, language: Option<String>) -> SlackBlock {
+    // Slack rejects empty text elements
+    let code = if code.is_empty() { " " } else { code };
+    let preformatted = SlackRichTextPreformatted::new(vec![code.into()]).opt_language(language);
+    SlackRichTextBlock::new(vec![preformatted.into()]).into()
+}
+
+fn rich_text_content(block: &serde_json::Value) -> String {
+    let elements = block["elements"].as_array().into_iter().flatten();
+    elements
+        .map(|element| {
+            let inline = element["elements"].as_array().into_iter().flatten();
+            inline
+                .filter_map(|e| e["text"].as_str())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // === Markdown rendering ===
+
+    fn render(input: &str) -> serde_json::Value {
+        serde_json::to_value(md_to_blocks(input, &MrkdwnOptions::default())).unwrap()
+    }
+
+    #[test]
+    fn test_heading_becomes_header() {
+        let blocks = render("# Title\nBody text");
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(blocks[0]["text"]["type"], "plain_text");
+        assert_eq!(blocks[0]["text"]["text"], "Title");
+        assert_eq!(blocks[1]["type"], "section");
+        assert_eq!(blocks[1]["text"]["type"], "mrkdwn");
+        assert_eq!(blocks[1]["text"]["text"], "Body text");
+    }
+
+    #[test]
+    fn test_heading_strips_inline_markdown() {
+        let blocks = render("## **Step 1**: `init`");
+        assert_eq!(blocks[0]["text"]["text"], "Step 1: init");
+    }
+
+    #[test]
+    fn test_long_heading_is_truncated() {
+        let blocks = render(&format!("# {}", "a".repeat(200)));
+        let text = blocks[0]["text"]["text"].as_str().unwrap();
+        assert_eq!(text.chars().count(), MAX_HEADER_TEXT_LENGTH);
+        assert!(text.ends_with('…'));
+    }
+
+    #[test]
+    fn test_hr_becomes_divider() {
+        let blocks = render("above\n\n---\n\nbelow");
+        assert_eq!(blocks.as_array().unwrap().len(), 3);
+        assert_eq!(blocks[1]["type"], "divider");
+    }
+
+    #[test]
+    fn test_code_becomes_preformatted() {
+        let blocks = render("Run:\n```rust\nfn main() {}\n```\nDone");
+        assert_eq!(blocks[1]["type"], "rich_text");
+        let pre = &blocks[1]["elements"][0];
+        assert_eq!(pre["type"], "rich_text_preformatted");
+        assert_eq!(pre["language"], "rust");
+        assert_eq!(pre["elements"][0]["text"], "fn main() {}");
+        assert_eq!(blocks[2]["text"]["text"], "Done");
+    }
+
+    #[test]
+    fn test_code_keeps_markdown_literal() {
+        let blocks = render("```\n# not a heading\n---\n```");
+        assert_eq!(blocks.as_array().unwrap().len(), 1);
+        assert_eq!(
+            blocks[0]["elements"][0]["elements"][0]["text"],
+            "# not a heading\n---"
+        );
+    }
+
+    #[test]
+    fn test_unterminated_code() {
+        let blocks = render("```\nlet x = 1;");
+        assert_eq!(blocks[0]["type"], "rich_text");
+        assert_eq!(
+            blocks[0]["elements"][0]["elements"][0]["text"],
+            "let x = 1;"
+        );
+    }
+
+    #[test]
+    fn test_section_uses_mrkdwn() {
+        let blocks = render("This is **bold** and a [link](https://example.com)");
+        assert_eq!(
+            blocks[0]["text"]["text"],
+            "This is *bold* and a <https://example.com|link>"
+        );
+    }
+
+    #[test]
+    fn test_long_section_is_split() {
+        let paragraph = "word ".repeat(500);
+        let input = format!("{}\n\n{}", paragraph.trim(), paragraph.trim());
+        let blocks = render(&input);
+        assert_eq!(blocks.as_array().unwrap().len(), 2);
+        for block in blocks.as_array().unwrap() {
+            assert_eq!(block["type"], "section");
+            assert!(block["text"]["text"].as_str().unwrap().len() <= MAX_SECTION_TEXT_LENGTH);
+        }
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert!(md_to_blocks("", &MrkdwnOptions::default()).is_empty());
+        assert!(md_to_blocks("\n\n", &MrkdwnOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_fallback_text() {
+        let blocks = md_to_blocks(
+            "# Title\nBody\n\n---\n```\ncode\n```",
+            &MrkdwnOptions::default(),
+        );
+        assert_eq!(fallback_text(&blocks), "Title\nBody\ncode");
+    }
+
+    // === Declarative builder ===
+
+    fn build(spec: serde_json::Value) -> serde_json::Value {
+        serde_json::to_value(build_blocks(&spec, None).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_build_object_order() {
+        let blocks = build(serde_json::json!({
+            "context": "footer",
+            "header": "Title",
+            "section": "body",
+            "divider": true,
+        }));
+        let types: Vec<_> = blocks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["header", "section", "context", "divider"]);
+        assert_eq!(blocks[2]["elements"][0]["text"], "footer");
+    }
+
+    #[test]
+    fn test_build_array_keeps_order() {
+        let blocks = build(serde_json::json!([
+            {"section": "first"},
+            {"divider": true},
+            {"sections": ["second", "third"]},
+        ]));
+        assert_eq!(blocks.as_array().unwrap().len(), 4);
+        assert_eq!(blocks[0]["text"]["text"], "first");
+        assert_eq!(blocks[1]["type"], "divider");
+        assert_eq!(blocks[3]["text"]["text"], "third");
+    }
+
+    #[test]
+    fn test_build_fields() {
+        let blocks = build(serde_json::json!({"fields": ["*Status*\nOK", "*Owner*\nalice"]}));
+        assert_eq!(blocks[0]["type"], "section");
+        assert_eq!(blocks[0]["fields"][1]["type"], "mrkdwn");
+        assert_eq!(blocks[0]["fields"][1]["text"], "*Owner*\nalice");
+    }
+
+    #[test]
+    fn test_build_buttons() {
+        let blocks = build(serde_json::json!({"buttons": [
+            {"text": "Approve", "action_id": "approve", "value": "1", "style": "primary"},
+            {"text": "Docs", "url": "https://example.com/"},
+        ]}));
+        assert_eq!(blocks[0]["type"], "actions");
+        let approve = &blocks[0]["elements"][0];
+        assert_eq!(approve["type"], "button");
+        assert_eq!(approve["text"]["text"], "Approve");
+        assert_eq!(approve["action_id"], "approve");
+        assert_eq!(approve["style"], "primary");
+        assert_eq!(blocks[0]["elements"][1]["url"], "https://example.com/");
+    }
+
+    #[test]
+    fn test_build_images() {
+        let blocks = build(serde_json::json!({"images": [
+            "https://example.com/a.png",
+            {"url": "https://example.com/b.png", "alt_text": "B", "title": "Chart"},
+        ]}));
+        assert_eq!(blocks[0]["type"], "image");
+        assert_eq!(blocks[0]["alt_text"], "https://example.com/a.png");
+        assert_eq!(blocks[1]["image_url"], "https://example.com/b.png");
+        assert_eq!(blocks[1]["alt_text"], "B");
+        assert_eq!(blocks[1]["title"]["text"], "Chart");
+    }
+
+    #[test]
+    fn test_build_converts_markdown() {
+        let spec = serde_json::json!({"section": "**bold**"});
+        let blocks = build_blocks(&spec, Some(&MrkdwnOptions::default())).unwrap();
+        let blocks = serde_json::to_value(blocks).unwrap();
+        assert_eq!(blocks[0]["text"]["text"], "*bold*");
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let err = |spec: serde_json::Value| build_blocks(&spec, None).unwrap_err().to_string();
+        assert!(err(serde_json::json!({"sectoin": "typo"})).contains("sectoin"));
+        assert!(err(serde_json::json!({"header": ""})).contains("header"));
+        assert!(
+            err(serde_json::json!({"buttons": [{"url": "https://example.com"}]}))
+                .contains("buttons.text")
+        );
+        assert!(
+            err(serde_json::json!({"buttons": [{"text": "x", "style": "blue"}]})).contains("blue")
+        );
+        assert!(err(serde_json::json!({"image": "not a url"})).contains("image url"));
+        assert!(err(serde_json::json!("text")).contains("object"));
+    }
+
+    // === Validation ===
+
+    #[test]
+    fn test_validate_valid_blocks() {
+        let blocks = md_to_blocks(
+            "# Title\nBody\n\n---\n```\ncode\n```",
+            &MrkdwnOptions::default(),
+        );
+        assert!(validate_blocks(&serde_json::to_value(blocks).unwrap()).is_ok());
+        let spec = serde_json::json!({
+            "fields": ["a", "b"],
+            "buttons": [{"text": "Go", "url": "https://example.com"}],
+            "context": "footer",
+            "image": "https://example.com/a.png",
+        });
+        let blocks = build_blocks(&spec, None).unwrap();
+        assert!(validate_blocks(&serde_json::to_value(blocks).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_not_array() {
+        let err = validate_blocks(&serde_json::json!({"type": "section"})).unwrap_err();
+        assert!(err.to_string().contains("expected an array"));
+    }
+
+    #[test]
+    fn test_validate_too_many_blocks() {
+        let blocks = serde_json::json!(vec![serde_json::json!({"type": "divider"}); 51]);
+        let err = validate_blocks(&blocks).unwrap_err().to_string();
+        assert!(err.contains("51 blocks given, at most 50"));
+    }
+
+    #[test]
+    fn test_validate_unknown_type() {
+        let blocks = serde_json::json!([{"type": "divider"}, {"type": "sectoin"}, {}]);
+        let err = validate_blocks(&blocks).unwrap_err().to_string();
+        assert!(err.contains("blocks[1] (sectoin): unknown block type"));
+        assert!(err.contains("blocks[2] (): missing type"));
+    }
+
+    #[test]
+    fn test_validate_text_lengths() {
+        let blocks = serde_json::json!([
+            {"type": "section", "text": {"type": "mrkdwn", "text": "a".repeat(3001)}},
+            {"type": "header", "text": {"type": "plain_text", "text": "a".repeat(151)}},
+            {"type": "actions", "elements": [
+                {"type": "button", "text": {"type": "plain_text", "text": "a".repeat(76)}}
+            ]},
+        ]);
+        let err = validate_blocks(&blocks).unwrap_err().to_string();
+        assert!(err.contains("blocks[0] (section): text is 3001 characters, at most 3000"));
+        assert!(err.contains("blocks[1] (header): text is 151 characters, at most 150"));
+        assert!(err.contains("blocks[2] (actions): elements[0].text is 76 characters, at most 75"));
+    }
+
+    #[test]
+    fn test_validate_required_fields() {
+        let blocks = serde_json::json!([
+            {"type": "section"},
+            {"type": "header", "text": {"type": "mrkdwn", "text": "x"}},
+            {"type": "image", "image_url": "https://example.com/a.png"},
+            {"type": "context", "elements": []},
+        ]);
+        let err = validate_blocks(&blocks).unwrap_err().to_string();
+        assert!(err.contains("blocks[0] (section): either text or fields is required"));
+        assert!(err.contains("blocks[1] (header): text must be plain_text"));
+        assert!(err.contains("blocks[2] (image): alt_text is required"));
+        assert!(err.contains("blocks[3] (context): elements must be a non-empty array"));
+    }
+
+    #[test]
+    fn test_validate_too_many_fields() {
+        let fields: Vec<_> = (0..11)
+            .map(|i| serde_json::json!({"type": "mrkdwn", "text": i.to_string()}))
+            .collect();
+        let blocks = serde_json::json!([{"type": "section", "fields": fields}]);
+        let err = validate_blocks(&blocks).unwrap_err().to_string();
+        assert!(err.contains("11 fields given, at most 10"));
+    }
+
+    #[test]
+    fn test_escape_special_mentions_in_blocks() {
+        let mut blocks = serde_json::json!([
+            {"type": "section", "text": {"type": "mrkdwn", "text": "<!channel> look"}},
+            {"type": "rich_text", "elements": [{"type": "rich_text_section", "elements": [
+                {"type": "broadcast", "range": "here"},
+            ]}]},
+        ]);
+        escape_special_mentions(&mut blocks);
+        assert_eq!(blocks[0]["text"]["text"], "@\u{200B}channel look");
+        assert_eq!(
+            blocks[1]["elements"][0]["elements"][0],
+            serde_json::json!({"type": "text", "text": "@\u{200B}here"})
+        );
+    }
+}
@@ -1,4 +1,6 @@
 #![recursion_limit = "256"]
 
 pub mod agents;
+pub mod blocks;
 pub mod mrkdwn;
+pub mod slack;
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::LazyLock;
 
 use regex::Regex;
@@ -6,6 +7,7 @@ struct Patterns {
     crlf: Regex,
     null_byte: Regex,
     fenced_code: Regex,
+    code_snippet: Regex,
     inline_code: Regex,
     table: Regex,
     html_bold_b: Regex,
@@ -40,6 +42,11 @@ struct Patterns {
     md_ul_star: Regex,
     md_hr: Regex,
     excess_newlines: Regex,
+    iso_timestamp: Regex,
+    special_mention: Regex,
+    special_mention_token: Regex,
+    handle_mention: Regex,
+    user_mention: Regex,
 }
 
 static RE: LazyLock<Patterns> = LazyLock::new(|| {
@@ -47,6 +54,7 @@ static RE: LazyLock<Patterns> = LazyLock::new(|| {
     crlf: Regex::new(r"\r\n").unwrap(),
     null_byte: Regex::new(r"\x00").unwrap(),
     fenced_code: Regex::new(r"(?s)```[^\n]*\n(.*?)```").unwrap(),
+    code_snippet: Regex::new(r"(?s)```([^\n`]*)\n(.*?)\n?```").unwrap(),
     inline_code: Regex::new(r"`([^`\n]+)`").unwrap(),
     table: Regex::new(r"(?m)((?:^[ \t]*\|.+\|[ \t]*\n)+^[ \t]*\|[\s:]*-[\s:\-|]*\|[ \t]*\n(?:^[ \t]*\|.+\|[ \t]*\n?)*)").unwrap(),
     html_bold_b: Regex::new(r"(?si)<b>(.*?)</b>").unwrap(),
@@ -81,11 +89,58 @@ static RE: LazyLock<Patterns> = LazyLock::new(|| {
     md_ul_star: Regex::new(r"(?m)^(\s*)\* ").unwrap(),
     md_hr: Regex::new(r"(?m)^[-*_]{3,}\s*$").unwrap(),
     excess_newlines: Regex::new(r"\n{3,}").unwrap(),
+    iso_timestamp: Regex::new(r"\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})").unwrap(),
+    special_mention: Regex::new(r"(^|[^\w@])@(here|channel|everyone)\b").unwrap(),
+    special_mention_token: Regex::new(r"<!(here|channel|everyone)(?:\|[^>]*)?>").unwrap(),
+    handle_mention: Regex::new(r"(^|[^\w@])@([\w.-]*\w)").unwrap(),
+    user_mention: Regex::new(r"<@([UW][A-Z0-9]+)(?:\|[^>]*)?>").unwrap(),
 }
 });
 
+/// Options for [`md_to_mrkdwn_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct MrkdwnOptions {
+    /// Slack date format (e.g. `{date_short_pretty} at {time}`) used to turn
+    /// ISO 8601 timestamps into `<!date^...>` tokens. `None` leaves them as-is.
+    pub date_format: Option<String>,
+    /// How `@here`, `@channel` and `@everyone` are rendered.
+    pub special_mentions: SpecialMentions,
+    /// User group handles (without `@`) mapped to their IDs. `@handle` is
+    /// converted to a `<!subteam^ID>` mention that notifies the group.
+    pub usergroups: HashMap<String, String>,
+}
+
+/// Handling of Slack's special mentions (`@here`, `@channel`, `@everyone`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpecialMentions {
+    /// Leave the text untouched.
+    #[default]
+    Keep,
+    /// Convert to `<!here>` etc. so Slack actually notifies the channel.
+    Convert,
+    /// Break up the mention (and any literal `<!here>` token) with a
+    /// zero-width space so nobody is notified.
+    Escape,
+}
+
+/// Breaks up `@here`, `@channel`, `@everyone` and their `<!here>` tokens with a
+/// zero-width space so nobody is notified.
+///
+/// Used for text that is sent without Markdown conversion.
+pub fn escape_special_mentions(text: &str) -> String {
+    let text = RE.special_mention_token.replace_all(text, "@\u{200B}$1");
+    RE.special_mention
+        .replace_all(&text, "$1@\u{200B}$2")
+        .into_owned()
+}
+
 /// Convert Markdown/HTML text to Slack mrkdwn format.
 pub fn md_to_mrkdwn(input: &str) -> String {
+    md_to_mrkdwn_with_options(input, &MrkdwnOptions::default())
+}
+
+/// Convert Markdown/HTML text to Slack mrkdwn format with extra options.
+pub fn md_to_mrkdwn_with_options(input: &str, options: &MrkdwnOptions) -> String {
     if input.is_empty() {
         return String::new();
     }
@@ -234,6 +289,27 @@ pub fn md_to_mrkdwn(input: &str) -> String {
         })
         .into_owned();
 
+    // Step 6b: ISO 8601 timestamps → Slack date tokens (protect). Runs after links
+    // are protected, and skips timestamps inside bare URLs or `<...>` tokens.
+    if let Some(format) = options.date_format.as_deref().filter(|f| !f.is_empty()) {
+        text = RE
+            .iso_timestamp
+            .replace_all(&text, |caps: &regex::Captures| {
+                let start = caps.get(0).map_or(0, |m| m.start());
+                let word = text[..start].rsplit(char::is_whitespace).next();
+                if word.is_some_and(|w| w.contains("://") || w.contains('<')) {
+                    return caps[0].to_string();
+                }
+                let Some(token) = iso_to_date_token(&caps[0], format) else {
+                    return caps[0].to_string();
+                };
+                let idx = placeholders.len();
+                placeholders.push(token);
+                format!("\x00DT{}\x00", idx)
+            })
+            .into_owned();
+    }
+
     // Strip remaining HTML tags
     text = RE.html_any_tag.replace_all(&text, "").into_owned();
 
@@ -244,6 +320,39 @@ pub fn md_to_mrkdwn(input: &str) -> String {
     text = RE.html_entity_apos.replace_all(&text, "'").into_owned();
     text = RE.html_entity_amp.replace_all(&text, "&").into_owned();
 
+    // Step 8: Special mentions (@here/@channel/@everyone)
+    match options.special_mentions {
+        SpecialMentions::Keep => {}
+        SpecialMentions::Convert => {
+            text = RE
+                .special_mention
+                .replace_all(&text, |caps: &regex::Captures| {
+                    let idx = placeholders.len();
+                    placeholders.push(format!("<!{}>", &caps[2]));
+                    format!("{}\x00MN{}\x00", &caps[1], idx)
+                })
+                .into_owned();
+        }
+        SpecialMentions::Escape => text = escape_special_mentions(&text),
+    }
+
+    // Step 8b: User group mentions (@handle → <!subteam^ID>)
+    if !options.usergroups.is_empty() {
+        text = RE
+            .handle_mention
+            .replace_all(&text, |caps: &regex::Captures| {
+                match options.usergroups.get(&caps[2]) {
+                    Some(id) => {
+                        let idx = placeholders.len();
+                        placeholders.push(format!("<!subteam^{}>", id));
+                        format!("{}\x00MN{}\x00", &caps[1], idx)
+                    }
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned();
+    }
+
     // Step 9: Bold/Italic conversion (order matters)
     // 9a: ***bold italic*** → *_bold italic_* → protect from italic pass
     text = RE
@@ -300,7 +409,7 @@ pub fn md_to_mrkdwn(input: &str) -> String {
 
     // Step 15: Restore all placeholders
     for (idx, replacement) in placeholders.iter().enumerate().rev() {
-        for prefix in &["CB", "IC", "TB", "LK", "BI", "BD"] {
+        for prefix in &["CB", "IC", "TB", "DT", "LK", "MN", "BI", "BD"] {
             let token = format!("\x00{}{}\x00", prefix, idx);
             if text.contains(&token) {
                 text = text.replace(&token, replacement);
@@ -326,10 +435,246 @@ pub fn md_to_mrkdwn(input: &str) -> String {
     text.trim().trim_matches('\u{200B}').to_string()
 }
 
+/// Maximum text length Slack recommends for a single message.
+pub const MAX_MESSAGE_LENGTH: usize = 4000;
+
+/// Split mrkdwn text into chunks of at most `max_len` characters.
+///
+/// Breaks at paragraph boundaries where possible, then at line boundaries, then
+/// at whitespace. Code blocks are never split at a blank line; a code block that
+/// is itself too long is closed at the end of a chunk and reopened in the next,
+/// so every chunk renders correctly on its own.
+pub fn split_mrkdwn(text: &str, max_len: usize) -> Vec<String> {
+    if max_len == 0 || char_len(text) <= max_len {
+        return vec![text.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    for block in split_paragraphs(text) {
+        if char_len(&block) <= max_len {
+            pieces.push(block);
+        } else {
+            pieces.extend(split_lines(&block, max_len));
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for piece in pieces {
+        if current.is_empty() {
+            current = piece;
+        } else if char_len(&current) + 2 + char_len(&piece) <= max_len {
+            current.push_str("\n\n");
+            current.push_str(&piece);
+        } else {
+            chunks.push(std::mem::replace(&mut current, piece));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Cuts `text` to at most `max_len` characters, ending it with `…` when cut.
+pub(crate) fn truncate(text: &str, max_len: usize) -> String {
+    if char_len(text) <= max_len {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_len - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn toggles_fence(line: &str) -> bool {
+    line.matches("```").count() % 2 == 1
+}
+
+/// Split text at blank lines that are outside code blocks.
+fn split_paragraphs(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_code = false;
+    for line in text.split('\n') {
+        if !in_code && line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(current.join("\n"));
+                current.clear();
+            }
+            continue;
+        }
+        if toggles_fence(line) {
+            in_code = !in_code;
+        }
+        current.push(line);
+    }
+    if !current.is_empty() {
+        blocks.push(current.join("\n"));
+    }
+    blocks
+}
+
+/// Split a block at line boundaries, closing and reopening code fences as needed.
+fn split_lines(block: &str, max_len: usize) -> Vec<String> {
+    const FENCE_OVERHEAD: usize = 8; // "```\n" + "\n```"
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut in_code = false;
+    for line in block.split('\n') {
+        let toggles = toggles_fence(line);
+        let line_max = if in_code || toggles {
+            max_len.saturating_sub(FENCE_OVERHEAD).max(1)
+        } else {
+            max_len
+        };
+        for part in split_words(line, line_max) {
+            // Reserve room for closing an open fence at the end of the chunk
+            let reserve = if in_code || toggles { 4 } else { 0 };
+            let sep = usize::from(!current.is_empty());
+            if !current.is_empty()
+                && current != "```"
+                && char_len(&current) + sep + char_len(&part) + reserve > max_len
+            {
+                if in_code {
+                    current.push_str("\n```");
+                }
+                chunks.push(std::mem::take(&mut current));
+                if in_code {
+                    current.push_str("```");
+                }
+            }
+            if !current.is_empty() {
+                current.push('\n');
+            }
+            current.push_str(&part);
+        }
+        if toggles {
+            in_code = !in_code;
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Split a single line at whitespace (or anywhere, if there is none) to fit `max_len`.
+fn split_words(line: &str, max_len: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut rest = line;
+    while char_len(rest) > max_len {
+        let limit = rest
+            .char_indices()
+            .nth(max_len)
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let cut = rest[..limit]
+            .rfind(char::is_whitespace)
+            .filter(|&i| i > 0)
+            .unwrap_or(limit);
+        parts.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start();
+    }
+    parts.push(rest.to_string());
+    parts
+}
+
 fn strip_angle_brackets(s: &str) -> String {
     s.replace(['<', '>'], "")
 }
 
+/// Build a Slack date token that renders `timestamp` (Unix seconds) in each
+/// reader's local timezone, falling back to `fallback` on older clients.
+///
+/// See: https://api.slack.com/reference/surfaces/formatting#date-formatting
+pub fn date_token(timestamp: i64, format: &str, fallback: &str) -> String {
+    format!(
+        "<!date^{}^{}|{}>",
+        timestamp,
+        format,
+        strip_angle_brackets(fallback).replace('|', "")
+    )
+}
+
+/// Convert an ISO 8601 timestamp with an explicit offset (`Z`, `+09:00`, `+0900`)
+/// into a Slack date token, using the original text as the fallback.
+pub fn iso_to_date_token(iso: &str, format: &str) -> Option<String> {
+    parse_iso_timestamp(iso).map(|ts| date_token(ts, format, iso))
+}
+
+fn parse_iso_timestamp(iso: &str) -> Option<i64> {
+    let normalized = iso.replacen(' ', "T", 1);
+    let normalized = match normalized.strip_suffix('Z') {
+        Some(body) => format!("{}+00:00", body),
+        None => normalized,
+    };
+    ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%dT%H:%M%z"]
+        .iter()
+        .find_map(|f| chrono::DateTime::parse_from_str(&normalized, f).ok())
+        .map(|dt| dt.timestamp())
+}
+
+/// User IDs mentioned as `<@U...>` in Slack text, in order of first appearance.
+pub fn mentioned_user_ids(text: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for caps in RE.user_mention.captures_iter(text) {
+        if !ids.iter().any(|id| id == &caps[1]) {
+            ids.push(caps[1].to_string());
+        }
+    }
+    ids
+}
+
+/// Rewrite `<@U...>` mentions in Slack text to `@name` using `names` (user ID to name).
+///
+/// Mentions of users missing from `names` are left untouched.
+pub fn replace_user_mentions(text: &str, names: &HashMap<String, String>) -> String {
+    RE.user_mention
+        .replace_all(text, |caps: &regex::Captures| match names.get(&caps[1]) {
+            Some(name) => format!("@{}", name),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// A fenced code block taken out of the text by [`extract_code_snippets`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeSnippet {
+    /// Language from the opening fence (e.g. `rust`), if any.
+    pub language: Option<String>,
+    /// The code, without the fences.
+    pub code: String,
+}
+
+/// Take fenced code blocks longer than `threshold` characters out of Markdown `text`.
+///
+/// Each removed block is replaced with a `_(code snippet N attached)_` note, numbered
+/// like the returned snippets, so the remaining prose still reads naturally.
+pub fn extract_code_snippets(text: &str, threshold: usize) -> (String, Vec<CodeSnippet>) {
+    let mut snippets = Vec::new();
+    let remaining = RE
+        .code_snippet
+        .replace_all(text, |caps: &regex::Captures| {
+            let code = &caps[2];
+            if char_len(code) <= threshold {
+                return caps[0].to_string();
+            }
+            let language = caps[1].trim();
+            snippets.push(CodeSnippet {
+                language: (!language.is_empty()).then(|| language.to_string()),
+                code: code.to_string(),
+            });
+            format!("_(code snippet {} attached)_", snippets.len())
+        })
+        .into_owned();
+    (remaining, snippets)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -724,4 +1069,321 @@ def hello():
     fn test_emoji_in_bold() {
         assert_eq!(md_to_mrkdwn("**🎉 celebration 🎉**"), "*🎉 celebration 🎉*");
     }
+
+    // === Date tokens ===
+
+    fn date_options() -> MrkdwnOptions {
+        MrkdwnOptions {
+            date_format: Some("{date_short_pretty} at {time}".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_date_token() {
+        assert_eq!(
+            date_token(1700000000, "{date}", "Nov 14"),
+            "<!date^1700000000^{date}|Nov 14>"
+        );
+    }
+
+    #[test]
+    fn test_iso_to_date_token_utc() {
+        assert_eq!(
+            iso_to_date_token("2024-01-15T10:30:00Z", "{time}").as_deref(),
+            Some("<!date^1705314600^{time}|2024-01-15T10:30:00Z>")
+        );
+    }
+
+    #[test]
+    fn test_iso_to_date_token_offset() {
+        assert_eq!(
+            iso_to_date_token("2024-01-15T19:30+09:00", "{time}").as_deref(),
+            Some("<!date^1705314600^{time}|2024-01-15T19:30+09:00>")
+        );
+        assert_eq!(
+            iso_to_date_token("2024-01-15 19:30:00.5+0900", "{time}").as_deref(),
+            Some("<!date^1705314600^{time}|2024-01-15 19:30:00.5+0900>")
+        );
+    }
+
+    #[test]
+    fn test_dates_converted_with_option() {
+        let output = md_to_mrkdwn_with_options("Deploy at 2024-01-15T10:30:00Z.", &date_options());
+        assert_eq!(
+            output,
+            "Deploy at <!date^1705314600^{date_short_pretty} at {time}|2024-01-15T10:30:00Z>."
+        );
+    }
+
+    #[test]
+    fn test_dates_untouched_by_default() {
+        assert_eq!(
+            md_to_mrkdwn("Deploy at 2024-01-15T10:30:00Z"),
+            "Deploy at 2024-01-15T10:30:00Z"
+        );
+    }
+
+    #[test]
+    fn test_dates_in_code_untouched() {
+        let output = md_to_mrkdwn_with_options("`2024-01-15T10:30:00Z`", &date_options());
+        assert_eq!(output, "`2024-01-15T10:30:00Z`");
+    }
+
+    #[test]
+    fn test_dates_in_links_untouched() {
+        let output = md_to_mrkdwn_with_options(
+            "[dash](https://g.example/?from=2024-01-15T10:30:00Z)",
+            &date_options(),
+        );
+        assert_eq!(
+            output,
+            "<https://g.example/?from=2024-01-15T10:30:00Z|dash>"
+        );
+
+        let output = md_to_mrkdwn_with_options(
+            "see https://g.example/?from=2024-01-15T10:30:00Z and <https://g.example/?to=2024-01-16T10:30:00Z>",
+            &date_options(),
+        );
+        assert!(!output.contains("<!date"), "{}", output);
+    }
+
+    #[test]
+    fn test_naive_date_untouched() {
+        let output = md_to_mrkdwn_with_options("at 2024-01-15T10:30:00", &date_options());
+        assert_eq!(output, "at 2024-01-15T10:30:00");
+    }
+
+    // === Special mentions ===
+
+    fn mention_options(special_mentions: SpecialMentions) -> MrkdwnOptions {
+        MrkdwnOptions {
+            special_mentions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_special_mentions_kept_by_default() {
+        assert_eq!(md_to_mrkdwn("hi @channel"), "hi @channel");
+    }
+
+    #[test]
+    fn test_special_mentions_converted() {
+        let options = mention_options(SpecialMentions::Convert);
+        assert_eq!(
+            md_to_mrkdwn_with_options("@here and @channel, @everyone!", &options),
+            "<!here> and <!channel>, <!everyone>!"
+        );
+    }
+
+    #[test]
+    fn test_special_mentions_in_bold_converted() {
+        let options = mention_options(SpecialMentions::Convert);
+        assert_eq!(
+            md_to_mrkdwn_with_options("**@here** look", &options),
+            "*<!here>* look"
+        );
+    }
+
+    #[test]
+    fn test_special_mentions_not_in_emails_or_words() {
+        let options = mention_options(SpecialMentions::Convert);
+        assert_eq!(
+            md_to_mrkdwn_with_options("mail ops@channel.io about @channels", &options),
+            "mail ops@channel.io about @channels"
+        );
+    }
+
+    #[test]
+    fn test_special_mentions_in_code_untouched() {
+        let options = mention_options(SpecialMentions::Convert);
+        assert_eq!(
+            md_to_mrkdwn_with_options("`@channel`", &options),
+            "`@channel`"
+        );
+    }
+
+    #[test]
+    fn test_special_mentions_escaped() {
+        let options = mention_options(SpecialMentions::Escape);
+        assert_eq!(
+            md_to_mrkdwn_with_options("hey @channel", &options),
+            "hey @\u{200B}channel"
+        );
+    }
+
+    #[test]
+    fn test_special_mention_tokens_escaped() {
+        let options = mention_options(SpecialMentions::Escape);
+        assert_eq!(
+            md_to_mrkdwn_with_options("hey &lt;!everyone&gt;", &options),
+            "hey @\u{200B}everyone"
+        );
+    }
+
+    #[test]
+    fn test_escape_special_mentions_raw_text() {
+        assert_eq!(
+            escape_special_mentions("<!here> and @channel, not ops@channel.io"),
+            "@\u{200B}here and @\u{200B}channel, not ops@channel.io"
+        );
+    }
+
+    // === User group mentions ===
+
+    fn usergroup_options() -> MrkdwnOptions {
+        MrkdwnOptions {
+            usergroups: HashMap::from([("oncall".to_string(), "S0123ABC".to_string())]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_usergroup_mention_converted() {
+        assert_eq!(
+            md_to_mrkdwn_with_options("@oncall please look", &usergroup_options()),
+            "<!subteam^S0123ABC> please look"
+        );
+    }
+
+    #[test]
+    fn test_usergroup_mention_in_bold() {
+        assert_eq!(
+            md_to_mrkdwn_with_options("**@oncall** now", &usergroup_options()),
+            "*<!subteam^S0123ABC>* now"
+        );
+    }
+
+    #[test]
+    fn test_unknown_handle_untouched() {
+        assert_eq!(
+            md_to_mrkdwn_with_options("@someone else", &usergroup_options()),
+            "@someone else"
+        );
+    }
+
+    #[test]
+    fn test_usergroup_not_in_email_or_code() {
+        assert_eq!(
+            md_to_mrkdwn_with_options("mail ops@oncall.io or `@oncall`", &usergroup_options()),
+            "mail ops@oncall.io or `@oncall`"
+        );
+    }
+
+    // === Splitting long messages ===
+
+    #[test]
+    fn test_split_short_text_unchanged() {
+        assert_eq!(split_mrkdwn("hello", 10), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_split_at_paragraphs() {
+        let chunks = split_mrkdwn("aaaa\n\nbbbb\n\ncccc", 10);
+        assert_eq!(chunks, vec!["aaaa\n\nbbbb", "cccc"]);
+    }
+
+    #[test]
+    fn test_split_at_lines() {
+        let chunks = split_mrkdwn("aaaa\nbbbb\ncccc", 10);
+        assert_eq!(chunks, vec!["aaaa\nbbbb", "cccc"]);
+    }
+
+    #[test]
+    fn test_split_long_line_at_whitespace() {
+        let chunks = split_mrkdwn("aaaa bbbb cccc dddd", 10);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 10));
+        assert_eq!(chunks.join(" "), "aaaa bbbb cccc dddd");
+    }
+
+    #[test]
+    fn test_split_keeps_code_block_with_blank_lines_together() {
+        let input = "intro\n\n```\na\n\nb\n```";
+        let chunks = split_mrkdwn(input, 16);
+        assert_eq!(chunks, vec!["intro", "```\na\n\nb\n```"]);
+    }
+
+    #[test]
+    fn test_split_long_code_block_reopens_fence() {
+        let code: Vec<String> = (0..10).map(|i| format!("line {}", i)).collect();
+        let input = format!("```\n{}\n```", code.join("\n"));
+        let chunks = split_mrkdwn(&input, 30);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 30, "{:?}", chunk);
+            assert!(chunk.starts_with("```"), "{:?}", chunk);
+            assert!(chunk.ends_with("```"), "{:?}", chunk);
+        }
+    }
+
+    #[test]
+    fn test_split_counts_characters_not_bytes() {
+        let chunks = split_mrkdwn("あいうえお", 5);
+        assert_eq!(chunks, vec!["あいうえお"]);
+    }
+
+    // === Code snippets ===
+
+    #[test]
+    fn test_extract_long_code_block() {
+        let input =
+            "Here is the script:\n\n```python\nprint('hello')\nprint('world')\n```\n\nRun it.";
+        let (text, snippets) = extract_code_snippets(input, 10);
+        assert_eq!(
+            text,
+            "Here is the script:\n\n_(code snippet 1 attached)_\n\nRun it."
+        );
+        assert_eq!(
+            snippets,
+            vec![CodeSnippet {
+                language: Some("python".to_string()),
+                code: "print('hello')\nprint('world')".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extract_keeps_short_code_block() {
+        let input = "a\n```\nlong enough code\n```\nb\n```\nx\n```";
+        let (text, snippets) = extract_code_snippets(input, 5);
+        assert_eq!(text, "a\n_(code snippet 1 attached)_\nb\n```\nx\n```");
+        assert_eq!(snippets.len(), 1);
+        assert_eq!(snippets[0].language, None);
+        assert_eq!(snippets[0].code, "long enough code");
+    }
+
+    #[test]
+    fn test_extract_numbers_snippets_in_order() {
+        let input = "```rust\nfn a() {}\n```\n\n```rust\nfn b() {}\n```";
+        let (text, snippets) = extract_code_snippets(input, 3);
+        assert_eq!(
+            text,
+            "_(code snippet 1 attached)_\n\n_(code snippet 2 attached)_"
+        );
+        assert_eq!(snippets[1].code, "fn b() {}");
+    }
+
+    // === Resolving user mentions ===
+
+    #[test]
+    fn test_mentioned_user_ids() {
+        assert_eq!(
+            mentioned_user_ids("<@U012AB> and <@W999|bob>, again <@U012AB>"),
+            vec!["U012AB", "W999"]
+        );
+        assert!(mentioned_user_ids("<#C012|general> <!here>").is_empty());
+    }
+
+    #[test]
+    fn test_replace_user_mentions() {
+        let names = HashMap::from([
+            ("U012AB".to_string(), "alice".to_string()),
+            ("W999".to_string(), "bob".to_string()),
+        ]);
+        assert_eq!(
+            replace_user_mentions("hi <@U012AB>, ask <@W999|robert> or <@U777>", &names),
+            "hi @alice, ask @bob or <@U777>"
+        );
+    }
 }
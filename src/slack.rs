@@ -0,0 +1,2543 @@
+//! Slack API helpers usable without the ModularAgent framework.
+//!
+//! The agents in [`crate::agents`] are built on top of these functions, so other
+//! crates can reuse the same client, token handling and upload logic directly.
+
+use std::collections::HashMap;
+use std::env;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::Duration;
+
+use modular_agent_core::AgentError;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use slack_morphism::errors::SlackClientError;
+use slack_morphism::prelude::*;
+use tracing::warn;
+
+use crate::mrkdwn;
+
+/// HTTP connector used by the shared Slack client.
+pub type HyperConnector = SlackClientHyperConnector<SlackHyperHttpsConnector>;
+
+/// Session type returned by `get_client().open_session(&token)`.
+pub type Session<'a> = SlackClientSession<'a, HyperConnector>;
+
+static CLIENT: OnceLock<SlackClient<HyperConnector>> = OnceLock::new();
+
+/// Returns the process-wide Slack client, initializing it on first use.
+pub fn get_client() -> &'static SlackClient<HyperConnector> {
+    CLIENT.get_or_init(|| {
+        rustls::crypto::ring::default_provider()
+            .install_default()
+            .expect("Failed to initialize rustls crypto provider");
+        SlackClient::new(
+            SlackClientHyperConnector::new().expect("Failed to create Slack client HTTP connector"),
+        )
+    })
+}
+
+/// Builds a bot token from `configured`, falling back to `SLACK_BOT_TOKEN`.
+pub fn bot_token(configured: Option<String>) -> Result<SlackApiToken, AgentError> {
+    token_or_env(configured, "SLACK_BOT_TOKEN")
+}
+
+/// Builds an app-level token from `configured`, falling back to `SLACK_APP_TOKEN`.
+pub fn app_token(configured: Option<String>) -> Result<SlackApiToken, AgentError> {
+    token_or_env(configured, "SLACK_APP_TOKEN")
+}
+
+/// Builds a user token from `configured`, falling back to `SLACK_USER_TOKEN`.
+pub fn user_token(configured: Option<String>) -> Result<SlackApiToken, AgentError> {
+    token_or_env(configured, "SLACK_USER_TOKEN")
+}
+
+fn token_or_env(configured: Option<String>, var: &str) -> Result<SlackApiToken, AgentError> {
+    let token_str = match configured.filter(|key| !key.is_empty()) {
+        Some(token) => token,
+        None => env::var(var).map_err(|_| AgentError::InvalidValue(format!("{} not set", var)))?,
+    };
+
+    Ok(SlackApiToken::new(SlackApiTokenValue(token_str)))
+}
+
+/// Optional parameters for [`post_message`].
+#[derive(Clone, Debug, Default)]
+pub struct PostOptions {
+    /// Post as a reply in this thread.
+    pub thread_ts: Option<String>,
+    /// Also show a thread reply in the channel.
+    pub reply_broadcast: bool,
+    /// Unfurl text-based links. `None` uses Slack's default.
+    pub unfurl_links: Option<bool>,
+    /// Unfurl media links. `None` uses Slack's default.
+    pub unfurl_media: Option<bool>,
+    /// Override the bot's display name (requires `chat:write.customize`).
+    pub username: Option<String>,
+    /// Override the bot's icon with an emoji such as `:robot_face:`.
+    pub icon_emoji: Option<String>,
+    /// Override the bot's icon with an image URL.
+    pub icon_url: Option<String>,
+    /// Structured metadata attached to the message.
+    pub metadata: Option<SlackMessageMetadata>,
+    /// How many times to retry when Slack rate-limits the request.
+    pub max_retries: u32,
+}
+
+impl PostOptions {
+    fn customizes_persona(&self) -> bool {
+        self.username.is_some() || self.icon_emoji.is_some() || self.icon_url.is_some()
+    }
+}
+
+/// Posts a message with optional blocks.
+pub async fn post_message(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    text: String,
+    blocks: Option<Vec<SlackBlock>>,
+    options: &PostOptions,
+) -> Result<SlackApiChatPostMessageResponse, SlackError> {
+    let mut content = SlackMessageContent::new().with_text(text);
+    if let Some(blocks) = blocks {
+        content = content.with_blocks(blocks);
+    }
+    if let Some(metadata) = &options.metadata {
+        content = content.with_metadata(metadata.clone());
+    }
+
+    let mut request = SlackApiChatPostMessageRequest::new(channel_id.clone(), content);
+    if let Some(ts) = &options.thread_ts {
+        request = request.with_thread_ts(ts.clone().into());
+        if options.reply_broadcast {
+            request = request.with_reply_broadcast(true);
+        }
+    }
+    if let Some(unfurl_links) = options.unfurl_links {
+        request = request.with_unfurl_links(unfurl_links);
+    }
+    if let Some(unfurl_media) = options.unfurl_media {
+        request = request.with_unfurl_media(unfurl_media);
+    }
+    if let Some(username) = &options.username {
+        request = request.with_username(username.clone());
+    }
+    if let Some(icon_emoji) = &options.icon_emoji {
+        request = request.with_icon_emoji(icon_emoji.clone());
+    }
+    if let Some(icon_url) = &options.icon_url {
+        request = request.with_icon_url(icon_url.clone());
+    }
+
+    retry_rate_limited(options.max_retries, || session.chat_post_message(&request))
+        .await
+        .map_err(|e| match api_error_code(&e) {
+            Some("missing_scope") if options.customizes_persona() => SlackError {
+                code: Some("missing_scope".to_string()),
+                error: AgentError::IoError(
+                    "Slack API error: missing_scope (overriding username or icon requires \
+                     the chat:write.customize scope)"
+                        .to_string(),
+                ),
+            },
+            _ => SlackError::api("Slack API error", e),
+        })
+}
+
+/// Runs `call`, retrying up to `max_retries` times while Slack rate-limits it.
+///
+/// Waits for the `Retry-After` duration, or an exponential backoff if that is
+/// shorter, before each retry.
+async fn retry_rate_limited<T, F, Fut>(max_retries: u32, mut call: F) -> ClientResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ClientResult<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match call().await {
+            Err(SlackClientError::RateLimitError(e)) if attempt < max_retries => {
+                let delay = retry_delay(e.retry_after, attempt);
+                warn!(
+                    "Rate limited by Slack, retrying in {:?} ({}/{})",
+                    delay,
+                    attempt + 1,
+                    max_retries
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn retry_delay(retry_after: Option<Duration>, attempt: u32) -> Duration {
+    const BASE_DELAY: Duration = Duration::from_secs(1);
+    const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+    let backoff = BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF);
+    retry_after.unwrap_or_default().max(backoff)
+}
+
+/// Posts a message through an incoming webhook, which needs no token.
+pub async fn post_webhook_message(
+    webhook_url: &str,
+    text: String,
+    blocks: Option<Vec<SlackBlock>>,
+) -> Result<(), SlackError> {
+    let url = webhook_url
+        .parse()
+        .map_err(|e| AgentError::InvalidConfig(format!("Invalid webhook URL: {}", e)))?;
+
+    let mut content = SlackMessageContent::new().with_text(text);
+    if let Some(blocks) = blocks {
+        content = content.with_blocks(blocks);
+    }
+    let request = SlackApiPostWebhookMessageRequest::new(content);
+
+    get_client()
+        .post_webhook_message(&url, &request)
+        .await
+        .map_err(webhook_error)?;
+    Ok(())
+}
+
+fn webhook_error(err: SlackClientError) -> SlackError {
+    match &err {
+        // Incoming webhooks answer errors with a bare code as the HTTP body
+        SlackClientError::HttpError(http_err) => SlackError {
+            code: http_err.http_response_body.clone().filter(|body| {
+                !body.is_empty() && body.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            }),
+            error: AgentError::IoError(format!("Slack API error: {}", err)),
+        },
+        _ => SlackError::api("Slack API error", err),
+    }
+}
+
+#[derive(Serialize)]
+struct ChatMeMessageRequest {
+    channel: SlackChannelId,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct ChatMeMessageResponse {
+    channel: SlackChannelId,
+    ts: SlackTs,
+}
+
+/// Posts a "/me"-style italic status line (`chat.meMessage`) and returns its
+/// channel and ts.
+///
+/// slack-morphism has no wrapper for this method, so the request goes through the
+/// session's raw HTTP API.
+pub async fn post_me_message(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    text: String,
+) -> Result<(SlackChannelId, SlackTs), AgentError> {
+    let request = ChatMeMessageRequest {
+        channel: channel_id.clone(),
+        text,
+    };
+
+    let response: ChatMeMessageResponse = session
+        .http_session_api
+        .http_post("chat.meMessage", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok((response.channel, response.ts))
+}
+
+/// A reminder as returned by the `reminders.*` methods.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    pub creator: Option<SlackUserId>,
+    pub user: Option<SlackUserId>,
+    pub text: String,
+    #[serde(default)]
+    pub recurring: bool,
+    /// Unix time the reminder fires, for one-off reminders.
+    pub time: Option<i64>,
+    /// Unix time the reminder was completed, `0` while pending.
+    pub complete_ts: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct RemindersAddRequest<'a> {
+    text: &'a str,
+    time: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<&'a SlackUserId>,
+}
+
+#[derive(Deserialize)]
+struct RemindersAddResponse {
+    reminder: Reminder,
+}
+
+#[derive(Serialize)]
+struct RemindersListRequest {}
+
+#[derive(Deserialize)]
+struct RemindersListResponse {
+    reminders: Vec<Reminder>,
+}
+
+#[derive(Serialize)]
+struct RemindersDeleteRequest<'a> {
+    reminder: &'a str,
+}
+
+#[derive(Deserialize)]
+struct EmptyResponse {}
+
+/// Creates a reminder (`reminders.add`, requires a user token with `reminders:write`).
+///
+/// `time` is a Unix time, a number of seconds from now, or natural language such as
+/// `in 2 hours` or `every Monday at 9am`. `user` defaults to the token's user.
+///
+/// slack-morphism has no wrapper for the reminders API, so these requests go through
+/// the session's raw HTTP API.
+pub async fn add_reminder(
+    session: &Session<'_>,
+    text: &str,
+    time: &str,
+    user: Option<&SlackUserId>,
+) -> Result<Reminder, AgentError> {
+    let request = RemindersAddRequest { text, time, user };
+    let response: RemindersAddResponse = session
+        .http_session_api
+        .http_post("reminders.add", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(response.reminder)
+}
+
+/// Lists the token user's reminders (`reminders.list`, requires `reminders:read`).
+pub async fn list_reminders(session: &Session<'_>) -> Result<Vec<Reminder>, AgentError> {
+    let response: RemindersListResponse = session
+        .http_session_api
+        .http_post("reminders.list", &RemindersListRequest {}, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(response.reminders)
+}
+
+/// Deletes a reminder (`reminders.delete`, requires `reminders:write`).
+pub async fn delete_reminder(session: &Session<'_>, reminder_id: &str) -> Result<(), AgentError> {
+    let request = RemindersDeleteRequest {
+        reminder: reminder_id,
+    };
+    let _: EmptyResponse = session
+        .http_session_api
+        .http_post("reminders.delete", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// A channel bookmark as returned by the `bookmarks.*` methods.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: String,
+    pub channel_id: SlackChannelId,
+    pub title: String,
+    pub link: Option<String>,
+    pub emoji: Option<String>,
+    #[serde(rename = "type")]
+    pub bookmark_type: Option<String>,
+    pub date_created: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct BookmarksListRequest<'a> {
+    channel_id: &'a SlackChannelId,
+}
+
+#[derive(Deserialize)]
+struct BookmarksListResponse {
+    bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Serialize)]
+struct BookmarksAddRequest<'a> {
+    channel_id: &'a SlackChannelId,
+    title: &'a str,
+    #[serde(rename = "type")]
+    bookmark_type: &'a str,
+    link: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emoji: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct BookmarksEditRequest<'a> {
+    channel_id: &'a SlackChannelId,
+    bookmark_id: &'a str,
+    title: &'a str,
+    link: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emoji: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct BookmarkResponse {
+    bookmark: Bookmark,
+}
+
+#[derive(Serialize)]
+struct BookmarksRemoveRequest<'a> {
+    channel_id: &'a SlackChannelId,
+    bookmark_id: &'a str,
+}
+
+/// Lists a channel's bookmarks (`bookmarks.list`, requires `bookmarks:read`).
+///
+/// slack-morphism has no wrapper for the bookmarks API, so these requests go through
+/// the session's raw HTTP API.
+pub async fn list_bookmarks(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<Vec<Bookmark>, AgentError> {
+    let response: BookmarksListResponse = session
+        .http_session_api
+        .http_post("bookmarks.list", &BookmarksListRequest { channel_id }, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(response.bookmarks)
+}
+
+/// Adds a link bookmark to a channel (`bookmarks.add`, requires `bookmarks:write`).
+pub async fn add_bookmark(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    title: &str,
+    link: &str,
+    emoji: Option<&str>,
+) -> Result<Bookmark, AgentError> {
+    let request = BookmarksAddRequest {
+        channel_id,
+        title,
+        bookmark_type: "link",
+        link,
+        emoji,
+    };
+    let response: BookmarkResponse = session
+        .http_session_api
+        .http_post("bookmarks.add", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(response.bookmark)
+}
+
+/// Changes a bookmark's title, link and emoji (`bookmarks.edit`, requires
+/// `bookmarks:write`).
+pub async fn edit_bookmark(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    bookmark_id: &str,
+    title: &str,
+    link: &str,
+    emoji: Option<&str>,
+) -> Result<Bookmark, AgentError> {
+    let request = BookmarksEditRequest {
+        channel_id,
+        bookmark_id,
+        title,
+        link,
+        emoji,
+    };
+    let response: BookmarkResponse = session
+        .http_session_api
+        .http_post("bookmarks.edit", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(response.bookmark)
+}
+
+/// Removes a bookmark from a channel (`bookmarks.remove`, requires `bookmarks:write`).
+pub async fn remove_bookmark(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    bookmark_id: &str,
+) -> Result<(), AgentError> {
+    let request = BookmarksRemoveRequest {
+        channel_id,
+        bookmark_id,
+    };
+    let _: EmptyResponse = session
+        .http_session_api
+        .http_post("bookmarks.remove", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Returns a permalink URL for a message.
+pub async fn get_permalink(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> Result<String, AgentError> {
+    let request = SlackApiChatGetPermalinkRequest::new(channel_id.clone(), ts.clone());
+
+    session
+        .chat_get_permalink(&request)
+        .await
+        .map(|response| response.permalink.to_string())
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))
+}
+
+/// Posts a message visible only to `user` in a channel.
+pub async fn post_ephemeral(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    user_id: &SlackUserId,
+    text: String,
+    blocks: Option<Vec<SlackBlock>>,
+    thread_ts: Option<String>,
+) -> Result<(), AgentError> {
+    let mut content = SlackMessageContent::new().with_text(text);
+    if let Some(blocks) = blocks {
+        content = content.with_blocks(blocks);
+    }
+
+    let mut request =
+        SlackApiChatPostEphemeralRequest::new(channel_id.clone(), user_id.clone(), content);
+    if let Some(ts) = thread_ts {
+        request = request.with_thread_ts(ts.into());
+    }
+
+    session
+        .chat_post_ephemeral(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(())
+}
+
+/// Replaces the text and blocks of a previously posted message.
+pub async fn update_message(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+    text: String,
+    blocks: Option<Vec<SlackBlock>>,
+) -> Result<SlackApiChatUpdateResponse, AgentError> {
+    let mut content = SlackMessageContent::new().with_text(text);
+    if let Some(blocks) = blocks {
+        content = content.with_blocks(blocks);
+    }
+
+    let request = SlackApiChatUpdateRequest::new(channel_id.clone(), content, ts.clone());
+
+    session
+        .chat_update(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))
+}
+
+/// Deletes a message.
+///
+/// `message_not_found` and `cant_delete_message` are reported as
+/// [`AgentError::InvalidValue`] so they can be told apart from transport errors.
+pub async fn delete_message(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> Result<SlackApiChatDeleteResponse, AgentError> {
+    let request = SlackApiChatDeleteRequest::new(channel_id.clone(), ts.clone());
+
+    session
+        .chat_delete(&request)
+        .await
+        .map_err(|e| match api_error_code(&e) {
+            Some("message_not_found") => AgentError::InvalidValue(format!(
+                "message_not_found: no message {} in {}",
+                ts, channel_id
+            )),
+            Some("cant_delete_message") => AgentError::InvalidValue(format!(
+                "cant_delete_message: not allowed to delete message {} in {}",
+                ts, channel_id
+            )),
+            _ => AgentError::IoError(format!("Slack API error: {}", e)),
+        })
+}
+
+/// Removes the caller's emoji reaction from a message (`reactions.remove`).
+pub async fn remove_reaction(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+    name: &str,
+) -> Result<(), AgentError> {
+    let request = SlackApiReactionsRemoveRequest::new(SlackReactionName(name.to_string()))
+        .with_channel(channel_id.clone())
+        .with_timestamp(ts.clone());
+
+    session
+        .reactions_remove(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Pins a message to its channel (`pins.add`).
+pub async fn pin_message(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> Result<(), AgentError> {
+    let request = SlackApiPinsAddRequest::new(channel_id.clone(), ts.clone());
+
+    session
+        .pins_add(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Unpins a message from its channel (`pins.remove`).
+pub async fn unpin_message(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> Result<(), AgentError> {
+    let request = SlackApiPinsRemoveRequest::new(channel_id.clone(), ts.clone());
+
+    session
+        .pins_remove(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Lists the messages pinned in a channel (`pins.list`, requires `pins:read`).
+pub async fn list_pins(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<Vec<SlackPin>, AgentError> {
+    let response = session
+        .pins_list(&SlackApiPinsListRequest::new(channel_id.clone()))
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.items)
+}
+
+/// An item that can be saved for later with [`add_star`].
+#[derive(Clone, Debug)]
+pub enum StarItem {
+    /// A message, identified by its channel and ts.
+    Message {
+        channel_id: SlackChannelId,
+        ts: SlackTs,
+    },
+    /// A file, identified by its ID.
+    File(SlackFileId),
+}
+
+/// Saves an item for later on behalf of the token's user (`stars.add`).
+pub async fn add_star(session: &Session<'_>, item: &StarItem) -> Result<(), AgentError> {
+    let request = match item {
+        StarItem::Message { channel_id, ts } => SlackApiStarsAddRequest::new()
+            .with_channel(channel_id.clone())
+            .with_timestamp(ts.clone()),
+        StarItem::File(file_id) => SlackApiStarsAddRequest::new().with_file(file_id.clone()),
+    };
+
+    session
+        .stars_add(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Removes an item from the token user's saved items (`stars.remove`).
+pub async fn remove_star(session: &Session<'_>, item: &StarItem) -> Result<(), AgentError> {
+    let request = match item {
+        StarItem::Message { channel_id, ts } => SlackApiStarsRemoveRequest::new()
+            .with_channel(channel_id.clone())
+            .with_timestamp(ts.clone()),
+        StarItem::File(file_id) => SlackApiStarsRemoveRequest::new().with_file(file_id.clone()),
+    };
+
+    session
+        .stars_remove(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Attaches custom previews to links in a message (`chat.unfurl`).
+///
+/// `id` is either the `channel`/`ts` of the message or the `unfurl_id`/`source` of a
+/// `link_shared` event, and `unfurls` maps each URL to the blocks shown as its preview.
+pub async fn unfurl(
+    session: &Session<'_>,
+    id: SlackUnfurlRequestId,
+    unfurls: HashMap<String, Vec<SlackBlock>>,
+) -> Result<(), AgentError> {
+    let unfurls = unfurls
+        .into_iter()
+        .map(|(url, blocks)| (url, SlackApiChatUnfurlMapItemV2::new(blocks)))
+        .collect();
+    let request = SlackApiChatUnfurlRequestV3::new(id, unfurls);
+
+    session
+        .chat_unfurl_v3(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Creates a canvas from Markdown and returns its ID.
+///
+/// With `channel_id` the canvas becomes that channel's canvas
+/// (`conversations.canvases.create`); otherwise a standalone canvas is created
+/// (`canvases.create`).
+pub async fn create_canvas(
+    session: &Session<'_>,
+    title: Option<String>,
+    markdown: String,
+    channel_id: Option<&SlackChannelId>,
+) -> Result<String, AgentError> {
+    let content = SlackCanvasDocumentContent::new(markdown);
+
+    let canvas_id = match channel_id {
+        Some(channel_id) => {
+            let request = SlackApiConversationsCanvasesCreateRequest::new(channel_id.clone())
+                .with_document_content(content)
+                .opt_title(title);
+            session
+                .conversations_canvases_create(&request)
+                .await
+                .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?
+                .canvas_id
+        }
+        None => {
+            let request = SlackApiCanvasesCreateRequest::new()
+                .with_document_content(content)
+                .opt_title(title);
+            session
+                .canvases_create(&request)
+                .await
+                .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?
+                .canvas_id
+        }
+    };
+    Ok(canvas_id.to_string())
+}
+
+/// Applies a single change to a canvas (`canvases.edit`).
+pub async fn edit_canvas(
+    session: &Session<'_>,
+    canvas_id: &str,
+    change: SlackCanvasChange,
+) -> Result<(), AgentError> {
+    let request = SlackApiCanvasesEditRequest::new(canvas_id.into(), vec![change]);
+
+    session
+        .canvases_edit(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Returns all reactions on a message and who added them (`reactions.get`).
+pub async fn get_reactions(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> Result<Vec<SlackReaction>, AgentError> {
+    let request = SlackApiReactionsGetRequest::new()
+        .with_channel(channel_id.clone())
+        .with_timestamp(ts.clone())
+        .with_full(true);
+
+    let response = session
+        .reactions_get(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    match response {
+        SlackApiReactionsGetResponse::Message(message) => {
+            Ok(message.message.content.reactions.unwrap_or_default())
+        }
+        SlackApiReactionsGetResponse::File(_) => Ok(Vec::new()),
+    }
+}
+
+/// Returns the Slack error code (e.g. `channel_not_found`) of an API error.
+pub fn api_error_code(err: &SlackClientError) -> Option<&str> {
+    match err {
+        SlackClientError::ApiError(api_err) => Some(api_err.code.as_str()),
+        SlackClientError::RateLimitError(rate_err) => {
+            Some(rate_err.code.as_deref().unwrap_or("ratelimited"))
+        }
+        _ => None,
+    }
+}
+
+/// A failed Slack call, with the Slack error code taken from the client error.
+///
+/// Returned by the helpers whose failures agents send to an `error` port. Converts
+/// into [`AgentError`], so `?` still works in functions returning one.
+#[derive(Debug)]
+pub struct SlackError {
+    /// Slack's error code, e.g. `channel_not_found`. `None` when the request failed
+    /// without one, such as a network failure or invalid input.
+    pub code: Option<String>,
+    pub error: AgentError,
+}
+
+impl SlackError {
+    /// Wraps `err` from a Slack API call, describing it as `context: err`.
+    fn api(context: &str, err: SlackClientError) -> Self {
+        Self {
+            code: api_error_code(&err).map(String::from),
+            error: AgentError::IoError(format!("{}: {}", context, err)),
+        }
+    }
+}
+
+impl std::fmt::Display for SlackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl From<AgentError> for SlackError {
+    fn from(error: AgentError) -> Self {
+        Self { code: None, error }
+    }
+}
+
+impl From<SlackError> for AgentError {
+    fn from(err: SlackError) -> Self {
+        err.error
+    }
+}
+
+/// A file to upload with [`upload_files`].
+#[derive(Clone, Debug, Default)]
+pub struct FileUpload {
+    /// File content.
+    pub bytes: Vec<u8>,
+    /// File name shown in Slack.
+    pub filename: String,
+    /// MIME type of the content (e.g. `image/png`).
+    pub content_type: String,
+    /// Title shown instead of the file name.
+    pub title: Option<String>,
+    /// Description of an image for screen readers.
+    pub alt_text: Option<String>,
+    /// Syntax highlighting for a text snippet (e.g. `python`).
+    pub snippet_type: Option<String>,
+}
+
+/// Uploads `bytes` as a file and shares it to `channel_id`.
+///
+/// Uses the external upload flow (`files.getUploadURLExternal` →
+/// upload → `files.completeUploadExternal`) and returns the new file ID.
+pub async fn upload_file(
+    session: &Session<'_>,
+    bytes: Vec<u8>,
+    filename: String,
+    content_type: String,
+    channel_id: &SlackChannelId,
+    initial_comment: Option<String>,
+    thread_ts: Option<String>,
+) -> Result<String, AgentError> {
+    let file = FileUpload {
+        bytes,
+        filename,
+        content_type,
+        ..Default::default()
+    };
+    let file_ids =
+        upload_files(session, vec![file], channel_id, initial_comment, thread_ts).await?;
+    Ok(file_ids.into_iter().next().unwrap_or_default())
+}
+
+/// Uploads several files and shares them to `channel_id` as a single message.
+///
+/// Each file goes through `files.getUploadURLExternal` and the upload itself, then
+/// all of them are completed in one `files.completeUploadExternal` call so they
+/// appear grouped. Returns the new file IDs in order.
+pub async fn upload_files(
+    session: &Session<'_>,
+    files: Vec<FileUpload>,
+    channel_id: &SlackChannelId,
+    initial_comment: Option<String>,
+    thread_ts: Option<String>,
+) -> Result<Vec<String>, SlackError> {
+    let mut completes = Vec::with_capacity(files.len());
+    for file in files {
+        // Step 1: Get upload URL
+        let upload_url_request =
+            SlackApiFilesGetUploadUrlExternalRequest::new(file.filename, file.bytes.len())
+                .opt_alt_txt(file.alt_text)
+                .opt_snippet_type(file.snippet_type.map(SlackFileSnippetType));
+
+        let upload_url_response = session
+            .get_upload_url_external(&upload_url_request)
+            .await
+            .map_err(|e| SlackError::api("Failed to get upload URL", e))?;
+
+        // Step 2: Upload file content
+        let upload_request = SlackApiFilesUploadViaUrlRequest::new(
+            upload_url_response.upload_url,
+            file.bytes,
+            file.content_type,
+        );
+
+        session
+            .files_upload_via_url(&upload_request)
+            .await
+            .map_err(|e| SlackError::api("Failed to upload file", e))?;
+
+        completes
+            .push(SlackApiFilesComplete::new(upload_url_response.file_id).opt_title(file.title));
+    }
+
+    // Step 3: Complete all uploads at once
+    let mut complete_request = SlackApiFilesCompleteUploadExternalRequest::new(completes)
+        .with_channel_id(channel_id.clone());
+
+    if let Some(comment) = initial_comment {
+        complete_request = complete_request.with_initial_comment(comment);
+    }
+
+    if let Some(ts) = thread_ts {
+        complete_request = complete_request.with_thread_ts(ts.into());
+    }
+
+    let complete_response = session
+        .files_complete_upload_external(&complete_request)
+        .await
+        .map_err(|e| SlackError::api("Failed to complete upload", e))?;
+
+    Ok(complete_response
+        .files
+        .iter()
+        .map(|f| f.id.to_string())
+        .collect())
+}
+
+/// Fetches up to `limit` of the most recent messages in a channel.
+pub async fn fetch_history(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    limit: u16,
+) -> Result<Vec<HistoryMessage>, AgentError> {
+    let request = SlackApiConversationsHistoryRequest::new()
+        .with_channel(channel_id.clone())
+        .with_limit(limit);
+
+    let response = conversations_history(session, &request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.messages)
+}
+
+/// Minimum interval between [`latest_message_ts`] peeks when checking many channels,
+/// keeping them within `conversations.history`'s Tier 3 rate limit (about 50
+/// requests per minute).
+pub const HISTORY_PEEK_INTERVAL: Duration = Duration::from_millis(1200);
+
+/// Peeks at a channel's history for the ts of its most recent message, `None` when
+/// the channel has no messages.
+///
+/// A rate-limited request is retried after Slack's `Retry-After` delay. Callers
+/// peeking at several channels should wait [`HISTORY_PEEK_INTERVAL`] between calls.
+pub async fn latest_message_ts(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<Option<SlackTs>, AgentError> {
+    let options = HistoryOptions {
+        limit: 1,
+        max_retries: 3,
+        ..Default::default()
+    };
+    let messages = fetch_history_with_options(session, channel_id, &options).await?;
+    Ok(messages.into_iter().next().map(|msg| msg.message.origin.ts))
+}
+
+/// Optional parameters for [`fetch_history_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct HistoryOptions {
+    /// Messages per request (Slack caps this at 999). `0` uses Slack's default.
+    pub limit: u16,
+    /// Follow `next_cursor` until the history is exhausted.
+    pub fetch_all: bool,
+    /// Stop paging once this many messages are fetched. `None` is unlimited.
+    pub max_messages: Option<usize>,
+    /// Retry a page up to this many times when Slack rate-limits it.
+    pub max_retries: u32,
+    /// Only messages after this ts (see [`parse_history_time`]).
+    pub oldest: Option<SlackTs>,
+    /// Only messages before this ts (see [`parse_history_time`]).
+    pub latest: Option<SlackTs>,
+}
+
+/// Parses a history time bound into a Slack ts.
+///
+/// Accepts a Slack ts or Unix time (`1700000000.000100`), an RFC 3339 timestamp
+/// (`2024-01-15T10:30:00Z`), or a time relative to `now` such as `-24h`, `-30m`,
+/// `-7d` or `-2w` (`s`, `m`, `h`, `d` and `w` units).
+pub fn parse_history_time(
+    expr: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<SlackTs, AgentError> {
+    let expr = expr.trim();
+    let invalid = || AgentError::InvalidValue(format!("Invalid history time: {}", expr));
+
+    if expr.parse::<f64>().is_ok() && expr.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Ok(SlackTs(expr.to_string()));
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(expr) {
+        return Ok(to_slack_ts(
+            time.timestamp(),
+            time.timestamp_subsec_micros(),
+        ));
+    }
+
+    let relative = expr.strip_prefix('-').ok_or_else(invalid)?;
+    let unit_at = relative.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = relative.split_at(unit_at);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let time = now - chrono::Duration::seconds(amount.saturating_mul(seconds));
+    Ok(to_slack_ts(
+        time.timestamp(),
+        time.timestamp_subsec_micros(),
+    ))
+}
+
+fn to_slack_ts(secs: i64, micros: u32) -> SlackTs {
+    SlackTs(format!("{}.{:06}", secs, micros))
+}
+
+/// Fetches the most recent messages in a channel, optionally following pagination.
+///
+/// Pages that are rate-limited are retried after Slack's `Retry-After` delay, so
+/// long fetches pace themselves. Messages are returned newest first.
+pub async fn fetch_history_with_options(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    options: &HistoryOptions,
+) -> Result<Vec<HistoryMessage>, AgentError> {
+    let mut pager = HistoryPager::new(channel_id.clone(), options.clone());
+    let mut messages = Vec::new();
+    while let Some(page) = pager.next_page(session).await? {
+        messages.extend(page);
+    }
+    Ok(messages)
+}
+
+/// Pages through a channel's history one `conversations.history` request at a time,
+/// so callers can process each page as it arrives.
+///
+/// Follows the same rules as [`fetch_history_with_options`]: only the first page is
+/// fetched unless `fetch_all` is set, and paging stops at `max_messages`.
+pub struct HistoryPager {
+    channel_id: SlackChannelId,
+    options: HistoryOptions,
+    cursor: Option<SlackCursorId>,
+    fetched: usize,
+    done: bool,
+}
+
+impl HistoryPager {
+    pub fn new(channel_id: SlackChannelId, options: HistoryOptions) -> Self {
+        Self {
+            channel_id,
+            options,
+            cursor: None,
+            fetched: 0,
+            done: false,
+        }
+    }
+
+    /// Fetches the next page, or returns `None` once the history is exhausted.
+    pub async fn next_page(
+        &mut self,
+        session: &Session<'_>,
+    ) -> Result<Option<Vec<HistoryMessage>>, AgentError> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let options = &self.options;
+        let mut request =
+            SlackApiConversationsHistoryRequest::new().with_channel(self.channel_id.clone());
+        if options.limit > 0 {
+            request = request.with_limit(options.limit);
+        }
+        request = request
+            .opt_oldest(options.oldest.clone())
+            .opt_latest(options.latest.clone());
+        if let Some(c) = self.cursor.take() {
+            request = request.with_cursor(c);
+        }
+
+        let response = retry_rate_limited(options.max_retries, || {
+            conversations_history(session, &request)
+        })
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+        let mut messages = response.messages;
+        self.fetched += messages.len();
+        if let Some(max) = options.max_messages
+            && self.fetched >= max
+        {
+            let over = self.fetched - max;
+            messages.truncate(messages.len() - over);
+            self.done = true;
+            return Ok(Some(messages));
+        }
+
+        self.cursor = response
+            .response_metadata
+            .and_then(|m| m.next_cursor)
+            .filter(|c| !c.0.is_empty());
+        self.done = !options.fetch_all || self.cursor.is_none();
+        Ok(Some(messages))
+    }
+}
+
+/// Fetches every message in a thread, including the parent message.
+pub async fn fetch_replies(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    thread_ts: &SlackTs,
+) -> Result<Vec<HistoryMessage>, AgentError> {
+    let mut messages = Vec::new();
+    let mut cursor: Option<SlackCursorId> = None;
+
+    loop {
+        let mut request =
+            SlackApiConversationsRepliesRequest::new(channel_id.clone(), thread_ts.clone())
+                .with_limit(200);
+        if let Some(c) = cursor.take() {
+            request = request.with_cursor(c);
+        }
+
+        let response = conversations_replies(session, &request)
+            .await
+            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+        messages.extend(response.messages);
+
+        cursor = response
+            .response_metadata
+            .and_then(|m| m.next_cursor)
+            .filter(|c| !c.0.is_empty());
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Looks up a user's name, preferring the display name over the real name.
+pub async fn fetch_user_name(
+    session: &Session<'_>,
+    user_id: &SlackUserId,
+) -> Result<String, AgentError> {
+    Ok(user_display_name(&fetch_user(session, user_id).await?))
+}
+
+/// Fetches a user's profile (`users.info`).
+pub async fn fetch_user(
+    session: &Session<'_>,
+    user_id: &SlackUserId,
+) -> Result<SlackUser, AgentError> {
+    let response = session
+        .users_info(&SlackApiUsersInfoRequest::new(user_id.clone()))
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.user)
+}
+
+/// Returns the workspace URL (e.g. `https://example.slack.com/`) from `auth.test`.
+pub async fn workspace_url(session: &Session<'_>) -> Result<String, AgentError> {
+    Ok(auth_test(session).await?.url.0.to_string())
+}
+
+/// Verifies the session's token with `auth.test` and returns who it belongs to.
+///
+/// Invalid, expired or revoked tokens are reported as [`AgentError::InvalidConfig`]
+/// naming the Slack error code, so they are easy to tell apart from network errors.
+pub async fn auth_test(session: &Session<'_>) -> Result<SlackApiAuthTestResponse, AgentError> {
+    session.auth_test().await.map_err(auth_error)
+}
+
+fn auth_error(err: SlackClientError) -> AgentError {
+    match err {
+        SlackClientError::ApiError(api_err)
+            if matches!(
+                api_err.code.as_str(),
+                "invalid_auth"
+                    | "not_authed"
+                    | "token_expired"
+                    | "token_revoked"
+                    | "account_inactive"
+            ) =>
+        {
+            AgentError::InvalidConfig(format!(
+                "Slack token is invalid or expired ({})",
+                api_err.code
+            ))
+        }
+        err => AgentError::IoError(format!("Slack API error: {}", err)),
+    }
+}
+
+/// Builds a message permalink from the workspace URL, like `chat.getPermalink` does
+/// but without a request per message.
+///
+/// Thread replies link to the reply inside its thread.
+pub fn message_permalink(
+    workspace_url: &str,
+    channel_id: &str,
+    ts: &str,
+    thread_ts: Option<&str>,
+) -> String {
+    let mut link = format!(
+        "{}/archives/{}/p{}",
+        workspace_url.trim_end_matches('/'),
+        channel_id,
+        ts.replace('.', "")
+    );
+    if let Some(thread_ts) = thread_ts.filter(|t| *t != ts) {
+        link.push_str(&format!("?thread_ts={}&cid={}", thread_ts, channel_id));
+    }
+    link
+}
+
+/// The message a permalink points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permalink {
+    pub channel_id: String,
+    pub ts: String,
+    /// The `thread_ts` query parameter, present when the link is to a thread reply.
+    pub thread_ts: Option<String>,
+}
+
+/// Parses a message permalink such as
+/// `https://example.slack.com/archives/C0123/p1700000000000100`, the inverse of
+/// [`message_permalink`].
+pub fn parse_permalink(url: &str) -> Option<Permalink> {
+    let (path, query) = url.trim().split_once('?').unwrap_or((url.trim(), ""));
+    let mut segments = path.split("/archives/").nth(1)?.split('/');
+    let channel_id = segments.next().filter(|c| is_channel_id(c))?;
+    let digits = segments.next()?.strip_prefix('p')?;
+    if digits.len() <= 6 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (secs, micros) = digits.split_at(digits.len() - 6);
+    let thread_ts = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("thread_ts="))
+        .filter(|t| !t.is_empty())
+        .map(String::from);
+    Some(Permalink {
+        channel_id: channel_id.to_string(),
+        ts: format!("{}.{}", secs, micros),
+        thread_ts,
+    })
+}
+
+/// Looks up the names of `user_ids` that are not in `cache` yet and adds them.
+///
+/// Users that cannot be looked up are skipped, so the caller can fall back to the ID.
+pub async fn resolve_user_names(
+    session: &Session<'_>,
+    user_ids: impl IntoIterator<Item = String>,
+    cache: &mut HashMap<String, String>,
+) {
+    for user_id in user_ids {
+        if cache.contains_key(&user_id) {
+            continue;
+        }
+        match fetch_user_name(session, &user_id.clone().into()).await {
+            Ok(name) => {
+                cache.insert(user_id, name);
+            }
+            Err(e) => warn!("Failed to resolve user {}: {}", user_id, e),
+        }
+    }
+}
+
+/// Resolves a user ID or an email address to a user ID.
+///
+/// Email addresses are looked up with `users.lookupByEmail`, which requires the
+/// `users:read.email` scope.
+pub async fn resolve_user_id(session: &Session<'_>, user: &str) -> Result<SlackUserId, SlackError> {
+    if !user.contains('@') {
+        return Ok(user.into());
+    }
+
+    Ok(lookup_user_by_email(session, user).await?.id)
+}
+
+/// Looks up a user by email address (`users.lookupByEmail`, requires the
+/// `users:read.email` scope).
+pub async fn lookup_user_by_email(
+    session: &Session<'_>,
+    email: &str,
+) -> Result<SlackUser, SlackError> {
+    let request = SlackApiUsersLookupByEmailRequest::new(EmailAddress(email.to_string()));
+    let response = session
+        .users_lookup_by_email(&request)
+        .await
+        .map_err(|e| SlackError::api("Slack API error", e))?;
+
+    Ok(response.user)
+}
+
+/// Opens (or reuses) the direct message channel with a user.
+pub async fn open_dm(
+    session: &Session<'_>,
+    user_id: &SlackUserId,
+) -> Result<SlackChannelId, SlackError> {
+    open_conversation(session, std::slice::from_ref(user_id)).await
+}
+
+/// Opens (or reuses) a direct message with one user, or a multi-person direct
+/// message with several (`conversations.open`, up to 8 users besides the caller).
+pub async fn open_conversation(
+    session: &Session<'_>,
+    user_ids: &[SlackUserId],
+) -> Result<SlackChannelId, SlackError> {
+    let request = SlackApiConversationsOpenRequest::new().with_users(user_ids.to_vec());
+    let response = session
+        .conversations_open(&request)
+        .await
+        .map_err(|e| SlackError::api("Slack API error", e))?;
+
+    Ok(response.channel.id)
+}
+
+/// Whether `channel` looks like a conversation ID (`C0123ABCD`, `G...`, `D...`)
+/// rather than a channel name.
+pub fn is_channel_id(channel: &str) -> bool {
+    channel.len() >= 9
+        && channel.starts_with(['C', 'G', 'D'])
+        && channel
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// Optional parameters for [`list_channels`].
+#[derive(Clone, Debug, Default)]
+pub struct ChannelListOptions {
+    /// Channels per request (Slack caps this at 1000). `0` uses Slack's default.
+    pub limit: u16,
+    /// Follow `next_cursor` until every channel is listed.
+    pub fetch_all: bool,
+    /// Stop paging once this many channels are listed. `None` is unlimited.
+    pub max_channels: Option<usize>,
+    /// Conversation types to list. Empty lists public channels only, like Slack.
+    pub types: Vec<SlackConversationType>,
+    /// Leave archived channels out of the listing.
+    pub exclude_archived: bool,
+    /// Only list channels whose name matches (see [`parse_name_pattern`]).
+    pub name_pattern: Option<Regex>,
+}
+
+/// Parses a channel name pattern: a regex between slashes (`/^proj-\d+$/`), or
+/// otherwise a glob where `*` matches any run of characters and `?` one character.
+///
+/// A leading `#` on a glob is ignored, so `#proj-*` matches `proj-alpha`.
+pub fn parse_name_pattern(pattern: &str) -> Result<Regex, AgentError> {
+    let pattern = pattern.trim();
+    let regex = match pattern
+        .strip_prefix('/')
+        .and_then(|p| p.strip_suffix('/'))
+        .filter(|p| !p.is_empty())
+    {
+        Some(regex) => regex.to_string(),
+        None => {
+            let glob = pattern.trim_start_matches('#');
+            let mut regex = String::from("^");
+            for c in glob.chars() {
+                match c {
+                    '*' => regex.push_str(".*"),
+                    '?' => regex.push('.'),
+                    c => regex.push_str(&regex::escape(&c.to_string())),
+                }
+            }
+            regex.push('$');
+            regex
+        }
+    };
+    Regex::new(&regex)
+        .map_err(|e| AgentError::InvalidConfig(format!("Invalid name pattern {}: {}", pattern, e)))
+}
+
+/// Parses comma-separated conversation types (`public_channel`, `private_channel`,
+/// `im`, `mpim`) as accepted by `conversations.list`.
+pub fn parse_conversation_types(types: &str) -> Result<Vec<SlackConversationType>, AgentError> {
+    types
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| match t {
+            "public_channel" => Ok(SlackConversationType::Public),
+            "private_channel" => Ok(SlackConversationType::Private),
+            "im" => Ok(SlackConversationType::Im),
+            "mpim" => Ok(SlackConversationType::Mpim),
+            other => Err(AgentError::InvalidConfig(format!(
+                "Unknown conversation type: {}",
+                other
+            ))),
+        })
+        .collect()
+}
+
+/// Minimum interval between `conversations.list` pages, keeping long listings
+/// within the method's Tier 2 rate limit (about 20 requests per minute).
+const CHANNEL_LIST_PAGE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Lists channels with `conversations.list`, following pagination when
+/// `options.fetch_all` is set.
+///
+/// Pages are paced by [`CHANNEL_LIST_PAGE_INTERVAL`], and rate-limited pages are
+/// retried after Slack's `Retry-After` delay.
+pub async fn list_channels(
+    session: &Session<'_>,
+    options: &ChannelListOptions,
+) -> Result<Vec<SlackChannelInfo>, AgentError> {
+    let mut channels = Vec::new();
+    let mut cursor: Option<SlackCursorId> = None;
+    loop {
+        let mut request = SlackApiConversationsListRequest::new().opt_cursor(cursor.take());
+        if options.limit > 0 {
+            request = request.with_limit(options.limit);
+        }
+        if !options.types.is_empty() {
+            request = request.with_types(options.types.clone());
+        }
+        if options.exclude_archived {
+            request = request.with_exclude_archived(true);
+        }
+        let response = retry_rate_limited(3, || session.conversations_list(&request))
+            .await
+            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+        channels.extend(response.channels.into_iter().filter(|ch| {
+            options.name_pattern.as_ref().is_none_or(|pattern| {
+                ch.name
+                    .as_deref()
+                    .is_some_and(|name| pattern.is_match(name))
+            })
+        }));
+        if let Some(max) = options.max_channels
+            && channels.len() >= max
+        {
+            channels.truncate(max);
+            return Ok(channels);
+        }
+
+        cursor = response
+            .response_metadata
+            .and_then(|m| m.next_cursor)
+            .filter(|c| !c.0.is_empty());
+        if !options.fetch_all || cursor.is_none() {
+            return Ok(channels);
+        }
+        tokio::time::sleep(CHANNEL_LIST_PAGE_INTERVAL).await;
+    }
+}
+
+/// Channel name to ID, shared by every agent so each name is listed only once.
+static CHANNEL_IDS: LazyLock<Mutex<HashMap<String, SlackChannelId>>> =
+    LazyLock::new(Default::default);
+
+/// Resolves a channel name (with or without `#`) or ID to a channel ID.
+///
+/// Names are looked up by paging through `conversations.list` for public and private
+/// channels, including archived ones. Every name seen on the way is cached for the
+/// process, so later lookups need no API call until [`forget_channel_id`] is called.
+pub async fn resolve_channel_id(
+    session: &Session<'_>,
+    channel: &str,
+) -> Result<SlackChannelId, SlackError> {
+    if is_channel_id(channel) {
+        return Ok(channel.into());
+    }
+    let name = channel.trim_start_matches('#');
+    if let Some(id) = cached_channel_id(name) {
+        return Ok(id);
+    }
+
+    let mut cursor: Option<SlackCursorId> = None;
+    loop {
+        let request = SlackApiConversationsListRequest::new()
+            .with_limit(1000)
+            .with_types(vec![
+                SlackConversationType::Public,
+                SlackConversationType::Private,
+            ])
+            .opt_cursor(cursor.take());
+        let response = retry_rate_limited(3, || session.conversations_list(&request))
+            .await
+            .map_err(|e| SlackError::api("Slack API error", e))?;
+
+        cache_channel_ids(&response.channels);
+        if let Some(id) = cached_channel_id(name) {
+            return Ok(id);
+        }
+
+        cursor = response
+            .response_metadata
+            .and_then(|m| m.next_cursor)
+            .filter(|c| !c.0.is_empty());
+        if cursor.is_none() {
+            return Err(SlackError {
+                code: Some("channel_not_found".to_string()),
+                error: AgentError::InvalidValue(format!("Channel not found: {}", channel)),
+            });
+        }
+    }
+}
+
+fn cached_channel_id(name: &str) -> Option<SlackChannelId> {
+    CHANNEL_IDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned()
+}
+
+fn cache_channel_ids(channels: &[SlackChannelInfo]) {
+    let mut ids = CHANNEL_IDS.lock().unwrap_or_else(|e| e.into_inner());
+    for ch in channels {
+        if let Some(name) = &ch.name {
+            ids.insert(name.clone(), ch.id.clone());
+        }
+    }
+}
+
+/// Drops cached names of `channel_id`, e.g. after the channel is renamed, so the
+/// next [`resolve_channel_id`] looks the new name up again.
+pub fn forget_channel_id(channel_id: &SlackChannelId) {
+    CHANNEL_IDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|_, id| id != channel_id);
+}
+
+/// Fetches a channel's details, including its member count (`conversations.info`).
+pub async fn fetch_channel_info(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<SlackChannelInfo, AgentError> {
+    let request =
+        SlackApiConversationsInfoRequest::new(channel_id.clone()).with_include_num_members(true);
+    let response = session
+        .conversations_info(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.channel)
+}
+
+/// Creates a public or private channel (`conversations.create`), caching its ID
+/// for [`resolve_channel_id`].
+///
+/// A leading `#` on `name` is ignored.
+pub async fn create_channel(
+    session: &Session<'_>,
+    name: &str,
+    is_private: bool,
+) -> Result<SlackChannelInfo, AgentError> {
+    let request = SlackApiConversationsCreateRequest::new(name.trim_start_matches('#').into())
+        .with_is_private(is_private);
+    let response = session
+        .conversations_create(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    cache_channel_ids(std::slice::from_ref(&response.channel));
+    Ok(response.channel)
+}
+
+/// Archives a channel (`conversations.archive`).
+pub async fn archive_channel(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<(), AgentError> {
+    let request = SlackApiConversationsArchiveRequest::new(channel_id.clone());
+
+    session
+        .conversations_archive(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Unarchives a channel (`conversations.unarchive`).
+pub async fn unarchive_channel(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<(), AgentError> {
+    let request = SlackApiConversationsUnarchiveRequest::new(channel_id.clone());
+
+    session
+        .conversations_unarchive(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Invites users to a channel (`conversations.invite`).
+pub async fn invite_to_channel(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    user_ids: &[SlackUserId],
+) -> Result<SlackChannelInfo, AgentError> {
+    let request = SlackApiConversationsInviteRequest::new(channel_id.clone(), user_ids.to_vec());
+    let response = session
+        .conversations_invite(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.channel)
+}
+
+/// Joins a public channel as the token's user (`conversations.join`).
+pub async fn join_channel(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<SlackChannelInfo, AgentError> {
+    let request = SlackApiConversationsJoinRequest::new(channel_id.clone());
+    let response = session
+        .conversations_join(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.channel)
+}
+
+/// Leaves a channel (`conversations.leave`).
+pub async fn leave_channel(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<(), AgentError> {
+    let request = SlackApiConversationsLeaveRequest::new(channel_id.clone());
+
+    session
+        .conversations_leave(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Renames a channel (`conversations.rename`), replacing its cached name.
+///
+/// A leading `#` on `name` is ignored.
+pub async fn rename_channel(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    name: &str,
+) -> Result<SlackChannelInfo, AgentError> {
+    let request = SlackApiConversationsRenameRequest::new(
+        channel_id.clone(),
+        name.trim_start_matches('#').into(),
+    );
+    let response = session
+        .conversations_rename(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    forget_channel_id(channel_id);
+    cache_channel_ids(std::slice::from_ref(&response.channel));
+    Ok(response.channel)
+}
+
+/// Maximum length of a channel topic or purpose.
+pub const MAX_TOPIC_LENGTH: usize = 250;
+
+/// Sets a channel's topic (`conversations.setTopic`), truncated to
+/// [`MAX_TOPIC_LENGTH`] characters.
+pub async fn set_channel_topic(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    topic: &str,
+) -> Result<String, AgentError> {
+    let topic = mrkdwn::truncate(topic, MAX_TOPIC_LENGTH);
+    let request = SlackApiConversationsSetTopicRequest::new(channel_id.clone(), topic.clone());
+
+    session
+        .conversations_set_topic(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(topic)
+}
+
+/// Sets a channel's purpose (`conversations.setPurpose`), truncated to
+/// [`MAX_TOPIC_LENGTH`] characters.
+pub async fn set_channel_purpose(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    purpose: &str,
+) -> Result<String, AgentError> {
+    let purpose = mrkdwn::truncate(purpose, MAX_TOPIC_LENGTH);
+    let request = SlackApiConversationsSetPurposeRequest::new(channel_id.clone(), purpose.clone());
+
+    session
+        .conversations_set_purpose(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(purpose)
+}
+
+#[derive(Serialize)]
+struct ConversationsMarkRequest<'a> {
+    channel: &'a SlackChannelId,
+    ts: &'a SlackTs,
+}
+
+/// Moves the token user's read cursor in a channel to `ts` (`conversations.mark`,
+/// requires a user token with `channels:write` or the matching scope for the
+/// conversation type).
+///
+/// slack-morphism has no wrapper for this method, so the request goes through the
+/// session's raw HTTP API.
+pub async fn mark_read(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+    ts: &SlackTs,
+) -> Result<(), AgentError> {
+    let request = ConversationsMarkRequest {
+        channel: channel_id,
+        ts,
+    };
+    let _: EmptyResponse = session
+        .http_session_api
+        .http_post("conversations.mark", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// Lists the user IDs of a channel's members, following pagination
+/// (`conversations.members`).
+pub async fn fetch_channel_members(
+    session: &Session<'_>,
+    channel_id: &SlackChannelId,
+) -> Result<Vec<SlackUserId>, AgentError> {
+    let mut members = Vec::new();
+    let mut cursor: Option<SlackCursorId> = None;
+    loop {
+        let request = SlackApiConversationsMembersRequest::new()
+            .with_channel(channel_id.clone())
+            .with_limit(1000)
+            .opt_cursor(cursor.take());
+        let response = retry_rate_limited(3, || session.conversations_members(&request))
+            .await
+            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+        members.extend(response.members);
+        cursor = response
+            .response_metadata
+            .and_then(|m| m.next_cursor)
+            .filter(|c| !c.0.is_empty());
+        if cursor.is_none() {
+            return Ok(members);
+        }
+    }
+}
+
+/// Lists files matching `request` (`files.list`) and `keep`, following pages until
+/// `max_files` files are collected or the list is exhausted.
+///
+/// `keep` filters on what `files.list` cannot, such as the file type. The request's
+/// `page` is ignored, and `count` defaults to 100 files per page.
+pub async fn list_files(
+    session: &Session<'_>,
+    request: &SlackApiFilesListRequest,
+    max_files: usize,
+    keep: impl Fn(&SlackFile) -> bool,
+) -> Result<Vec<SlackFileInfo>, AgentError> {
+    let mut files = Vec::new();
+    let mut page = 1;
+    loop {
+        let request = SlackApiFilesListRequest {
+            count: request.count.or(Some(100)),
+            page: Some(page),
+            ..request.clone()
+        };
+        let response = retry_rate_limited(3, || files_list(session, &request))
+            .await
+            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+        let fetched = response.files.len();
+        files.extend(response.files.into_iter().filter(|f| keep(&f.file)));
+        if files.len() >= max_files {
+            files.truncate(max_files);
+            return Ok(files);
+        }
+
+        let pages = response.paging.and_then(|p| p.pages).unwrap_or(page);
+        if fetched == 0 || page >= pages {
+            return Ok(files);
+        }
+        page += 1;
+    }
+}
+
+/// Fetches the workspace's name, domain and icon (`team.info`).
+pub async fn fetch_team_info(session: &Session<'_>) -> Result<SlackTeamInfo, AgentError> {
+    let response = session
+        .team_info(&SlackApiTeamInfoRequest::new())
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.team)
+}
+
+/// Lists the workspace's users, following pagination (`users.list`).
+///
+/// Stops once `max_users` users are fetched; `None` fetches everyone.
+pub async fn list_users(
+    session: &Session<'_>,
+    max_users: Option<usize>,
+) -> Result<Vec<SlackUser>, AgentError> {
+    let mut users = Vec::new();
+    let mut cursor: Option<SlackCursorId> = None;
+    loop {
+        let request = SlackApiUsersListRequest::new()
+            .with_limit(200)
+            .opt_cursor(cursor.take());
+        let response = retry_rate_limited(3, || session.users_list(&request))
+            .await
+            .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+        users.extend(response.members);
+        if let Some(max) = max_users
+            && users.len() >= max
+        {
+            users.truncate(max);
+            return Ok(users);
+        }
+
+        cursor = response
+            .response_metadata
+            .and_then(|m| m.next_cursor)
+            .filter(|c| !c.0.is_empty());
+        if cursor.is_none() {
+            return Ok(users);
+        }
+    }
+}
+
+/// Fetches whether a user is `active` or `away` (`users.getPresence`).
+pub async fn fetch_presence(
+    session: &Session<'_>,
+    user_id: &SlackUserId,
+) -> Result<SlackApiUsersGetPresenceResponse, AgentError> {
+    session
+        .users_get_presence(&SlackApiUsersGetPresenceRequest::new(user_id.clone()))
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))
+}
+
+/// Lists the workspace's user groups with their user counts (`usergroups.list`).
+pub async fn list_usergroups(session: &Session<'_>) -> Result<Vec<SlackUserGroup>, SlackError> {
+    let response = session
+        .usergroups_list(&SlackApiUserGroupsListRequest::new().with_include_count(true))
+        .await
+        .map_err(|e| SlackError::api("Slack API error", e))?;
+
+    Ok(response.usergroups)
+}
+
+/// Resolves a user group handle (with or without `@`) or ID to a user group ID.
+pub async fn resolve_usergroup_id(
+    session: &Session<'_>,
+    usergroup: &str,
+) -> Result<SlackUserGroupId, AgentError> {
+    let usergroup = usergroup.trim().trim_start_matches('@');
+    if usergroup.starts_with('S')
+        && usergroup.len() >= 9
+        && usergroup
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        return Ok(usergroup.into());
+    }
+
+    list_usergroups(session)
+        .await?
+        .into_iter()
+        .find(|g| g.handle == usergroup)
+        .map(|g| g.id)
+        .ok_or_else(|| AgentError::InvalidValue(format!("Usergroup not found: @{}", usergroup)))
+}
+
+/// Lists the user IDs in a user group (`usergroups.users.list`).
+pub async fn fetch_usergroup_users(
+    session: &Session<'_>,
+    usergroup_id: &SlackUserGroupId,
+) -> Result<Vec<SlackUserId>, AgentError> {
+    let request = SlackApiUserGroupsUsersListRequest::new(usergroup_id.clone());
+    let response = session
+        .usergroups_users_list(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.users)
+}
+
+#[derive(Serialize)]
+struct UserGroupsUsersUpdateRequest {
+    usergroup: SlackUserGroupId,
+    users: String,
+}
+
+/// Replaces the members of a user group (`usergroups.users.update`, requires the
+/// `usergroups:write` scope).
+///
+/// slack-morphism has no wrapper for this method, so the request goes through the
+/// session's raw HTTP API.
+pub async fn update_usergroup_users(
+    session: &Session<'_>,
+    usergroup_id: &SlackUserGroupId,
+    users: &[SlackUserId],
+) -> Result<(), AgentError> {
+    let request = UserGroupsUsersUpdateRequest {
+        usergroup: usergroup_id.clone(),
+        users: users
+            .iter()
+            .map(|u| u.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    };
+
+    let _: EmptyResponse = session
+        .http_session_api
+        .http_post("usergroups.users.update", &request, None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(())
+}
+
+/// A user's Do Not Disturb settings as returned by `dnd.info` / `dnd.teamInfo`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DndStatus {
+    #[serde(default)]
+    pub dnd_enabled: bool,
+    /// Unix time the next (or current) scheduled DND window starts.
+    pub next_dnd_start_ts: Option<i64>,
+    /// Unix time the next (or current) scheduled DND window ends.
+    pub next_dnd_end_ts: Option<i64>,
+    /// Whether the user snoozed notifications manually. Only `dnd.info` reports this.
+    #[serde(default)]
+    pub snooze_enabled: bool,
+    pub snooze_endtime: Option<i64>,
+}
+
+impl DndStatus {
+    /// Whether notifications are paused at `now` (Unix time).
+    pub fn is_active(&self, now: i64) -> bool {
+        self.available_at(now).is_some()
+    }
+
+    /// When notifications resume, if they are paused at `now` (Unix time).
+    pub fn available_at(&self, now: i64) -> Option<i64> {
+        let snooze_end = self
+            .snooze_endtime
+            .filter(|end| self.snooze_enabled && *end > now);
+        let window_end = match (self.next_dnd_start_ts, self.next_dnd_end_ts) {
+            (Some(start), Some(end)) if self.dnd_enabled && start <= now && now < end => Some(end),
+            _ => None,
+        };
+        snooze_end.max(window_end)
+    }
+}
+
+#[derive(Deserialize)]
+struct DndTeamInfoResponse {
+    users: HashMap<String, DndStatus>,
+}
+
+/// Fetches a user's Do Not Disturb settings (`dnd.info`, requires `dnd:read`).
+///
+/// slack-morphism has no wrapper for the dnd API, so these requests go through the
+/// session's raw HTTP API.
+pub async fn fetch_dnd_info(
+    session: &Session<'_>,
+    user_id: &SlackUserId,
+) -> Result<DndStatus, AgentError> {
+    session
+        .http_session_api
+        .http_get("dnd.info", &vec![("user", Some(user_id.0.as_str()))], None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))
+}
+
+/// Fetches the Do Not Disturb settings of up to 50 users at once (`dnd.teamInfo`),
+/// keyed by user ID. Snoozes are not reported.
+pub async fn fetch_dnd_team_info(
+    session: &Session<'_>,
+    user_ids: &[SlackUserId],
+) -> Result<HashMap<String, DndStatus>, AgentError> {
+    let users = user_ids
+        .iter()
+        .map(|u| u.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let response: DndTeamInfoResponse = session
+        .http_session_api
+        .http_get("dnd.teamInfo", &vec![("users", Some(users.as_str()))], None)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+    Ok(response.users)
+}
+
+/// Fetches an app's bot user details, including its name and icons (`bots.info`,
+/// requires `users:read`).
+pub async fn fetch_bot_info(
+    session: &Session<'_>,
+    bot_id: &SlackBotId,
+) -> Result<SlackBotInfo, AgentError> {
+    let request = SlackApiBotsInfoRequest::new().with_bot(bot_id.to_string());
+    let response = session
+        .bots_info(&request)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.bot)
+}
+
+/// Picks the most human-friendly name available for a user.
+pub fn user_display_name(user: &SlackUser) -> String {
+    let profile = user.profile.as_ref();
+    profile
+        .and_then(|p| p.display_name.clone())
+        .filter(|n| !n.is_empty())
+        .or_else(|| profile.and_then(|p| p.real_name.clone()))
+        .filter(|n| !n.is_empty())
+        .or_else(|| user.real_name.clone())
+        .filter(|n| !n.is_empty())
+        .or_else(|| user.name.clone())
+        .unwrap_or_else(|| user.id.to_string())
+}
+
+/// Fetches a file's metadata (`files.info`).
+pub async fn fetch_file_info(
+    session: &Session<'_>,
+    file_id: &SlackFileId,
+) -> Result<SlackFileInfo, AgentError> {
+    let response = files_info(session, file_id)
+        .await
+        .map_err(|e| AgentError::IoError(format!("Slack API error: {}", e)))?;
+
+    Ok(response.file)
+}
+
+/// A [`SlackFile`] with its size in bytes, which `SlackFile` does not model.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SlackFileInfo {
+    #[serde(flatten)]
+    pub file: SlackFile,
+    pub size: Option<u64>,
+}
+
+/// A history message whose attached files keep their sizes.
+///
+/// `files` takes the message's `files` key, so `message.content.files` is always
+/// `None`; read the files from here instead.
+#[derive(Clone, Debug, Deserialize)]
+pub struct HistoryMessage {
+    #[serde(flatten)]
+    pub message: SlackHistoryMessage,
+    #[serde(default)]
+    pub files: Vec<SlackFileInfo>,
+}
+
+#[derive(Deserialize)]
+struct HistoryResponse {
+    messages: Vec<HistoryMessage>,
+    response_metadata: Option<SlackResponseMetadata>,
+}
+
+#[derive(Deserialize)]
+struct FilesListResponse {
+    files: Vec<SlackFileInfo>,
+    paging: Option<SlackApiFilesListPaging>,
+}
+
+#[derive(Deserialize)]
+struct FilesInfoResponse {
+    file: SlackFileInfo,
+}
+
+async fn conversations_history(
+    session: &Session<'_>,
+    req: &SlackApiConversationsHistoryRequest,
+) -> ClientResult<HistoryResponse> {
+    let params = vec![
+        ("channel", req.channel.as_ref().map(|x| x.to_string())),
+        ("cursor", req.cursor.as_ref().map(|x| x.to_string())),
+        ("limit", req.limit.map(|v| v.to_string())),
+        ("inclusive", req.inclusive.map(|v| v.to_string())),
+        ("latest", req.latest.as_ref().map(|x| x.to_string())),
+        ("oldest", req.oldest.as_ref().map(|x| x.to_string())),
+    ];
+    session
+        .http_session_api
+        .http_get("conversations.history", &params, None)
+        .await
+}
+
+async fn conversations_replies(
+    session: &Session<'_>,
+    req: &SlackApiConversationsRepliesRequest,
+) -> ClientResult<HistoryResponse> {
+    let params = vec![
+        ("channel", Some(req.channel.to_string())),
+        ("ts", Some(req.ts.to_string())),
+        ("cursor", req.cursor.as_ref().map(|x| x.to_string())),
+        ("limit", req.limit.map(|v| v.to_string())),
+    ];
+    session
+        .http_session_api
+        .http_get("conversations.replies", &params, None)
+        .await
+}
+
+async fn files_list(
+    session: &Session<'_>,
+    req: &SlackApiFilesListRequest,
+) -> ClientResult<FilesListResponse> {
+    let params = vec![
+        ("channel", req.channel.as_ref().map(|x| x.to_string())),
+        ("user", req.user.as_ref().map(|x| x.to_string())),
+        ("types", req.types.clone()),
+        ("count", req.count.map(|x| x.to_string())),
+        ("page", req.page.map(|x| x.to_string())),
+        ("ts_from", req.ts_from.map(|x| x.to_string())),
+        ("ts_to", req.ts_to.map(|x| x.to_string())),
+    ];
+    session
+        .http_session_api
+        .http_get("files.list", &params, None)
+        .await
+}
+
+async fn files_info(
+    session: &Session<'_>,
+    file_id: &SlackFileId,
+) -> ClientResult<FilesInfoResponse> {
+    let params = vec![("file", Some(file_id.to_string()))];
+    session
+        .http_session_api
+        .http_get("files.info", &params, None)
+        .await
+}
+
+/// Extracts the file ID from a private file URL such as
+/// `https://files.slack.com/files-pri/T0123-F0456/report.csv`.
+pub fn file_id_from_url(url: &str) -> Option<SlackFileId> {
+    let path = url.split("/files-pri/").nth(1)?;
+    let (_team, rest) = path.split_once('-')?;
+    let id = rest.split(['/', '?']).next()?;
+    (id.starts_with('F') && id.len() > 1).then(|| id.into())
+}
+
+/// Whether `url` points at a Slack-hosted file (`https://*.slack.com`), the only
+/// hosts the bot token may be sent to.
+pub fn is_slack_file_url(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|u| {
+        u.scheme() == "https"
+            && u.host_str()
+                .is_some_and(|host| host == "slack.com" || host.ends_with(".slack.com"))
+    })
+}
+
+/// Downloads a private Slack file (e.g. `url_private_download`) with a bot token.
+///
+/// URLs outside `*.slack.com` are rejected so the token never leaves Slack.
+pub async fn download_file(url: &str, bot_token: &str) -> Result<Vec<u8>, AgentError> {
+    if !is_slack_file_url(url) {
+        return Err(AgentError::InvalidValue(format!(
+            "Refusing to download a file outside slack.com: {}",
+            url
+        )));
+    }
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(url)
+        .header("Authorization", format!("Bearer {}", bot_token))
+        .send()
+        .await
+        .map_err(|e| AgentError::IoError(format!("Failed to fetch file: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(AgentError::IoError(format!(
+            "Failed to download file: HTTP {}",
+            response.status()
+        )));
+    }
+
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| AgentError::IoError(format!("Failed to read file bytes: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slack_morphism::errors::{SlackClientApiError, SlackClientHttpError, SlackRateLimitError};
+
+    #[test]
+    fn test_slack_error_code() {
+        let err = SlackError::api(
+            "Slack API error",
+            SlackClientError::ApiError(SlackClientApiError::new("channel_not_found".to_string())),
+        );
+        assert_eq!(err.code.as_deref(), Some("channel_not_found"));
+
+        let err = SlackError::api(
+            "Slack API error",
+            SlackClientError::RateLimitError(SlackRateLimitError::new()),
+        );
+        assert_eq!(err.code.as_deref(), Some("ratelimited"));
+
+        let err = SlackError::from(AgentError::InvalidValue("Channel".to_string()));
+        assert_eq!(err.code, None);
+    }
+
+    #[test]
+    fn test_webhook_error_code() {
+        let err = webhook_error(SlackClientError::HttpError(
+            SlackClientHttpError::new(reqwest::StatusCode::NOT_FOUND)
+                .with_http_response_body("no_service".to_string()),
+        ));
+        assert_eq!(err.code.as_deref(), Some("no_service"));
+    }
+
+    #[test]
+    fn test_auth_error() {
+        let err = auth_error(SlackClientError::ApiError(SlackClientApiError::new(
+            "token_revoked".to_string(),
+        )));
+        assert!(matches!(err, AgentError::InvalidConfig(_)));
+        assert!(err.to_string().contains("token_revoked"));
+
+        let err = auth_error(SlackClientError::ApiError(SlackClientApiError::new(
+            "ratelimited".to_string(),
+        )));
+        assert!(matches!(err, AgentError::IoError(_)));
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        assert_eq!(retry_delay(None, 0), Duration::from_secs(1));
+        assert_eq!(retry_delay(None, 3), Duration::from_secs(8));
+        assert_eq!(retry_delay(None, 20), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_retry_delay_honors_retry_after() {
+        let retry_after = Some(Duration::from_secs(30));
+        assert_eq!(retry_delay(retry_after, 0), Duration::from_secs(30));
+        assert_eq!(retry_delay(retry_after, 6), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_parse_history_time_ts() {
+        let now = chrono::Utc::now();
+        assert_eq!(
+            parse_history_time("1700000000.000100", now).unwrap(),
+            SlackTs("1700000000.000100".to_string())
+        );
+        assert_eq!(
+            parse_history_time("1700000000", now).unwrap(),
+            SlackTs("1700000000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_history_time_rfc3339() {
+        let now = chrono::Utc::now();
+        assert_eq!(
+            parse_history_time("2023-11-14T22:13:20Z", now).unwrap(),
+            SlackTs("1700000000.000000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_history_time_relative() {
+        let now = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(
+            parse_history_time("-24h", now).unwrap(),
+            SlackTs("1699913600.000000".to_string())
+        );
+        assert_eq!(
+            parse_history_time("-30m", now).unwrap(),
+            SlackTs("1699998200.000000".to_string())
+        );
+        assert_eq!(
+            parse_history_time("-1w", now).unwrap(),
+            SlackTs("1699395200.000000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_history_time_invalid() {
+        let now = chrono::Utc::now();
+        assert!(parse_history_time("yesterday", now).is_err());
+        assert!(parse_history_time("-24", now).is_err());
+        assert!(parse_history_time("-h", now).is_err());
+        assert!(parse_history_time("-", now).is_err());
+    }
+
+    #[test]
+    fn test_message_permalink() {
+        assert_eq!(
+            message_permalink(
+                "https://example.slack.com/",
+                "C0123",
+                "1700000000.000100",
+                None
+            ),
+            "https://example.slack.com/archives/C0123/p1700000000000100"
+        );
+        assert_eq!(
+            message_permalink(
+                "https://example.slack.com/",
+                "C0123",
+                "1700000000.000100",
+                Some("1700000000.000100")
+            ),
+            "https://example.slack.com/archives/C0123/p1700000000000100"
+        );
+    }
+
+    #[test]
+    fn test_message_permalink_thread_reply() {
+        assert_eq!(
+            message_permalink(
+                "https://example.slack.com",
+                "C0123",
+                "1700000100.000200",
+                Some("1700000000.000100")
+            ),
+            "https://example.slack.com/archives/C0123/p1700000100000200?thread_ts=1700000000.000100&cid=C0123"
+        );
+    }
+
+    #[test]
+    fn test_parse_permalink() {
+        assert_eq!(
+            parse_permalink("https://example.slack.com/archives/C0123ABCD/p1700000000000100"),
+            Some(Permalink {
+                channel_id: "C0123ABCD".to_string(),
+                ts: "1700000000.000100".to_string(),
+                thread_ts: None,
+            })
+        );
+        assert_eq!(
+            parse_permalink(
+                "https://example.slack.com/archives/C0123ABCD/p1700000100000200?thread_ts=1700000000.000100&cid=C0123ABCD"
+            ),
+            Some(Permalink {
+                channel_id: "C0123ABCD".to_string(),
+                ts: "1700000100.000200".to_string(),
+                thread_ts: Some("1700000000.000100".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_permalink("https://example.slack.com/archives/C0123ABCD"),
+            None
+        );
+        assert_eq!(
+            parse_permalink("https://example.slack.com/archives/C0123ABCD/pabc"),
+            None
+        );
+        assert_eq!(parse_permalink("https://example.com/"), None);
+    }
+
+    #[test]
+    fn test_parse_conversation_types() {
+        assert_eq!(
+            parse_conversation_types("public_channel, private_channel,im,mpim").unwrap(),
+            vec![
+                SlackConversationType::Public,
+                SlackConversationType::Private,
+                SlackConversationType::Im,
+                SlackConversationType::Mpim,
+            ]
+        );
+        assert!(parse_conversation_types("").unwrap().is_empty());
+        assert!(parse_conversation_types("channel").is_err());
+    }
+
+    #[test]
+    fn test_parse_name_pattern() {
+        let glob = parse_name_pattern("proj-*").unwrap();
+        assert!(glob.is_match("proj-alpha"));
+        assert!(glob.is_match("proj-"));
+        assert!(!glob.is_match("old-proj-alpha"));
+
+        let glob = parse_name_pattern("#team-?.ops").unwrap();
+        assert!(glob.is_match("team-a.ops"));
+        assert!(!glob.is_match("team-a-ops"));
+        assert!(!glob.is_match("team-ab.ops"));
+
+        let regex = parse_name_pattern(r"/^proj-\d+$/").unwrap();
+        assert!(regex.is_match("proj-42"));
+        assert!(!regex.is_match("proj-alpha"));
+
+        assert!(parse_name_pattern("/(/").is_err());
+    }
+
+    #[test]
+    fn test_is_channel_id() {
+        assert!(is_channel_id("C0123ABCD"));
+        assert!(is_channel_id("G0123ABCDEF"));
+        assert!(is_channel_id("D0123ABCD"));
+        assert!(!is_channel_id("general"));
+        assert!(!is_channel_id("#general"));
+        assert!(!is_channel_id("C0123"));
+        assert!(!is_channel_id("U0123ABCD"));
+    }
+
+    #[test]
+    fn test_file_id_from_url() {
+        assert_eq!(
+            file_id_from_url("https://files.slack.com/files-pri/T0123-F0456/report.csv"),
+            Some("F0456".into())
+        );
+        assert_eq!(
+            file_id_from_url(
+                "https://files.slack.com/files-pri/T0123-F0456/download/report.csv?origin_team=T0123"
+            ),
+            Some("F0456".into())
+        );
+        assert_eq!(file_id_from_url("https://example.com/report.csv"), None);
+    }
+
+    #[test]
+    fn test_history_response_file_sizes() {
+        let response: HistoryResponse = serde_json::from_value(serde_json::json!({
+            "messages": [
+                {"ts": "1.0", "text": "report", "files": [{"id": "F0456", "size": 1024}]},
+                {"ts": "2.0", "user": "U0123", "text": "hi"},
+            ]
+        }))
+        .unwrap();
+        let [with_file, plain] = response.messages.as_slice() else {
+            panic!("expected two messages");
+        };
+        assert_eq!(with_file.message.origin.ts.to_string(), "1.0");
+        assert_eq!(with_file.message.content.text.as_deref(), Some("report"));
+        assert_eq!(with_file.files.len(), 1);
+        assert_eq!(with_file.files[0].file.id.to_string(), "F0456");
+        assert_eq!(with_file.files[0].size, Some(1024));
+        assert!(plain.files.is_empty());
+
+        let response: FilesInfoResponse = serde_json::from_value(serde_json::json!({
+            "file": {"id": "F0789", "name": "notes.txt"}
+        }))
+        .unwrap();
+        assert_eq!(response.file.file.name.as_deref(), Some("notes.txt"));
+        assert_eq!(response.file.size, None);
+    }
+
+    #[test]
+    fn test_is_slack_file_url() {
+        assert!(is_slack_file_url(
+            "https://files.slack.com/files-pri/T0123-F0456/report.csv"
+        ));
+        assert!(!is_slack_file_url("https://example.com/report.csv"));
+        assert!(!is_slack_file_url("https://files.slack.com.example.com/x"));
+        assert!(!is_slack_file_url(
+            "http://files.slack.com/files-pri/T0123-F0456/x"
+        ));
+    }
+
+    #[test]
+    fn test_dnd_status_window() {
+        let status = DndStatus {
+            dnd_enabled: true,
+            next_dnd_start_ts: Some(1000),
+            next_dnd_end_ts: Some(2000),
+            ..Default::default()
+        };
+        assert_eq!(status.available_at(999), None);
+        assert_eq!(status.available_at(1000), Some(2000));
+        assert!(status.is_active(1500));
+        assert!(!status.is_active(2000));
+
+        let disabled = DndStatus {
+            dnd_enabled: false,
+            ..status
+        };
+        assert!(!disabled.is_active(1500));
+    }
+
+    #[test]
+    fn test_dnd_status_snooze() {
+        let status = DndStatus {
+            snooze_enabled: true,
+            snooze_endtime: Some(3000),
+            ..Default::default()
+        };
+        assert_eq!(status.available_at(100), Some(3000));
+        assert!(!status.is_active(3000));
+
+        let both = DndStatus {
+            dnd_enabled: true,
+            next_dnd_start_ts: Some(1000),
+            next_dnd_end_ts: Some(4000),
+            ..status
+        };
+        assert_eq!(both.available_at(1500), Some(4000));
+    }
+
+    #[test]
+    fn test_channel_id_cache() {
+        let channel = |id: &str, name: &str| -> SlackChannelInfo {
+            serde_json::from_value(serde_json::json!({"id": id, "name": name, "created": 0}))
+                .unwrap()
+        };
+        cache_channel_ids(&[
+            channel("C0CACHE01", "cache-a"),
+            channel("C0CACHE02", "cache-b"),
+        ]);
+        assert_eq!(cached_channel_id("cache-a"), Some("C0CACHE01".into()));
+
+        forget_channel_id(&"C0CACHE01".into());
+        assert_eq!(cached_channel_id("cache-a"), None);
+        assert_eq!(cached_channel_id("cache-b"), Some("C0CACHE02".into()));
+    }
+}